@@ -9,7 +9,7 @@ use rtt_target::{rprintln, rtt_init_print};
 use cortex_m::asm;
 use cortex_m_rt::entry;
 use stm32f3xx_hal::{pac, prelude::*, spi::Spi};
-use tmag5170::{self, alert_config, device_config, sensor_config, system_config, ExtError};
+use tmag5170::{self, alert_config, device_config, sensor_config, system_config};
 
 #[entry]
 fn main() -> ! {
@@ -104,10 +104,7 @@ fn main() -> ! {
         let res = tmag5170.read_am();
 
         match res {
-            Err(ext) => match ext {
-                ExtError::CrcError => rprintln!("Crc Error"),
-                ExtError::E(_e) => rprintln!("SPI Error"),
-            },
+            Err(e) => rprintln!("{}", e),
             Ok((a, m)) => rprintln!("Angle {:3} deg, magnitude {}", a / 8, m),
         }
     }
@@ -1,4 +1,7 @@
 /// Latched ALERT Mode Select
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum AlertLatch {
     /// ALERT sources are not latched. ALERT is asserted only
     /// while the source of the ALERT response is present
@@ -11,7 +14,19 @@ pub enum AlertLatch {
     Latched = 0x01,
 }
 
+impl AlertLatch {
+    pub(crate) fn from_bits(bits: u16) -> Self {
+        match bits {
+            0x00 => AlertLatch::NotLatched,
+            _ => AlertLatch::Latched,
+        }
+    }
+}
+
 /// Latched ALERT Mode Select
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum AlertMode {
     /// Interrupt Mode
     Interrupt = 0x00,
@@ -22,8 +37,20 @@ pub enum AlertMode {
     Comparator = 0x01,
 }
 
+impl AlertMode {
+    pub(crate) fn from_bits(bits: u16) -> Self {
+        match bits {
+            0x00 => AlertMode::Interrupt,
+            _ => AlertMode::Comparator,
+        }
+    }
+}
+
 /// Number of conversions above the HIGH Threshold or below the
 /// LOW Threshold before the ALERT Response is initiated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum ThrxCount {
     /// 1-Conversion Result
     ConvResult1 = 0x00,
@@ -38,12 +65,27 @@ pub enum ThrxCount {
     ConvResult4 = 0x03,
 }
 
+impl ThrxCount {
+    pub(crate) fn from_bits(bits: u16) -> Self {
+        match bits {
+            0x00 => ThrxCount::ConvResult1,
+            0x01 => ThrxCount::ConvResult2,
+            0x02 => ThrxCount::ConvResult3,
+            _ => ThrxCount::ConvResult4,
+        }
+    }
+}
+
 /// Configure Device Operation Modes - ALERT_CONFIG
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct AlertConfig {
     config: u16,
 }
 
 impl AlertConfig {
+    /// Reserved bits of ALERT_CONFIG
+    pub const RESERVED_BITS: u16 = 0xc6c0;
+
     /// Creates default config
     pub fn new() -> Self {
         let config = 0x00;
@@ -113,6 +155,51 @@ impl AlertConfig {
         self.config = self.config & !(0b1) | (x_thrx_alrt_enable as u16);
         self
     }
+
+    /// Get AlertLatch field
+    pub fn alert_latch(&self) -> AlertLatch {
+        AlertLatch::from_bits((self.config >> 13) & 0b1)
+    }
+
+    /// Get AlertMode field
+    pub fn alert_mode(&self) -> AlertMode {
+        AlertMode::from_bits((self.config >> 12) & 0b1)
+    }
+
+    /// Is STATUS_ALRT enabled
+    pub fn status_alrt_enable(&self) -> bool {
+        self.config & (0b1 << 11) != 0
+    }
+
+    /// Is RSLT_ALRT enabled
+    pub fn rslt_alrt_enable(&self) -> bool {
+        self.config & (0b1 << 8) != 0
+    }
+
+    /// Get ThrxCount field
+    pub fn thrx_count(&self) -> ThrxCount {
+        ThrxCount::from_bits((self.config >> 4) & 0b11)
+    }
+
+    /// Is T_THRX_ALRT enabled
+    pub fn t_thrx_alrt_enable(&self) -> bool {
+        self.config & (0b1 << 3) != 0
+    }
+
+    /// Is Z_THRX_ALRT enabled
+    pub fn z_thrx_alrt_enable(&self) -> bool {
+        self.config & (0b1 << 2) != 0
+    }
+
+    /// Is Y_THRX_ALRT enabled
+    pub fn y_thrx_alrt_enable(&self) -> bool {
+        self.config & (0b1 << 1) != 0
+    }
+
+    /// Is X_THRX_ALRT enabled
+    pub fn x_thrx_alrt_enable(&self) -> bool {
+        self.config & 0b1 != 0
+    }
 }
 
 impl Default for AlertConfig {
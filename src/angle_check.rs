@@ -0,0 +1,198 @@
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::health::Fault;
+use crate::retry::Backoff;
+use crate::sensor_config::AngleEn;
+use crate::{ExtError, Tmag5170};
+
+/// Angle in degrees held by ANGLE_RESULT, 4 fractional bits
+pub(crate) fn angle_degrees(angle_result: u16) -> f32 {
+    (angle_result & 0x1fff) as f32 / 16.0
+}
+
+/// 12-bit magnitude held by MAGNITUDE_RESULT
+pub(crate) fn magnitude_code(magnitude_result: u16) -> u16 {
+    magnitude_result & 0x0fff
+}
+
+/// Allowed disagreement between the on-chip and the software angle calculation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AngleTolerance {
+    angle: f32,
+    magnitude: f32,
+    min_magnitude: f32,
+}
+
+impl AngleTolerance {
+    /// Creates tolerance of 2 degrees, 5% of the magnitude and no minimum magnitude
+    pub fn new() -> Self {
+        AngleTolerance {
+            angle: 2.0,
+            magnitude: 0.05,
+            min_magnitude: 0.0,
+        }
+    }
+
+    /// Set allowed angle difference in degrees
+    pub fn set_angle(mut self, degrees: f32) -> Self {
+        self.angle = degrees;
+        self
+    }
+
+    /// Set allowed magnitude difference as a fraction of the software magnitude
+    pub fn set_magnitude(mut self, ratio: f32) -> Self {
+        self.magnitude = ratio;
+        self
+    }
+
+    /// Set magnitude, in MAGNITUDE_RESULT codes, below which the angle is not compared
+    pub fn set_min_magnitude(mut self, codes: f32) -> Self {
+        self.min_magnitude = codes;
+        self
+    }
+
+    /// Get allowed angle difference in degrees
+    pub fn angle(&self) -> f32 {
+        self.angle
+    }
+
+    /// Get allowed magnitude difference as a fraction
+    pub fn magnitude(&self) -> f32 {
+        self.magnitude
+    }
+
+    /// Get magnitude below which the angle is not compared
+    pub fn min_magnitude(&self) -> f32 {
+        self.min_magnitude
+    }
+}
+
+impl Default for AngleTolerance {
+    fn default() -> Self {
+        AngleTolerance::new()
+    }
+}
+
+/// On-chip angle and magnitude next to the values computed from the channel results
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AngleCheck {
+    /// ANGLE_RESULT in degrees
+    pub angle: f32,
+
+    /// atan2 of the two selected channels in degrees, 0 to 360
+    pub computed_angle: f32,
+
+    /// MAGNITUDE_RESULT in codes
+    pub magnitude: f32,
+
+    /// Magnitude of the two selected channels in MAGNITUDE_RESULT codes
+    pub computed_magnitude: f32,
+
+    /// Angles agree within the tolerance, or the field is too weak to compare them
+    pub angle_ok: bool,
+
+    /// Magnitudes agree within the tolerance
+    pub magnitude_ok: bool,
+}
+
+impl AngleCheck {
+    /// Compares ANGLE_RESULT and MAGNITUDE_RESULT with the raw results of the two channels
+    /// selected by AngleEn, `first` being the reference axis (X for Xy, Y for Yz, Z for Zx)
+    pub fn compare(
+        first: i16,
+        second: i16,
+        angle_result: u16,
+        magnitude_result: u16,
+        tolerance: AngleTolerance,
+    ) -> Self {
+        let angle = angle_degrees(angle_result);
+        let magnitude = magnitude_code(magnitude_result) as f32;
+
+        let mut computed_angle = libm::atan2f(second as f32, first as f32).to_degrees();
+        if computed_angle < 0.0 {
+            computed_angle += 360.0;
+        }
+        // MAGNITUDE_RESULT holds the 12 most significant bits of the 16-bit magnitude
+        let computed_magnitude =
+            libm::sqrtf((first as f32) * (first as f32) + (second as f32) * (second as f32)) / 16.0;
+
+        let angle_ok = computed_magnitude < tolerance.min_magnitude
+            || angle_difference(angle, computed_angle) <= tolerance.angle;
+        // One code is added for the rounding of the on-chip result
+        let magnitude_ok = libm::fabsf(magnitude - computed_magnitude)
+            <= computed_magnitude * tolerance.magnitude + 1.0;
+
+        AngleCheck {
+            angle,
+            computed_angle,
+            magnitude,
+            computed_magnitude,
+            angle_ok,
+            magnitude_ok,
+        }
+    }
+
+    /// Returns true if the on-chip results are plausible
+    pub fn is_ok(&self) -> bool {
+        self.angle_ok && self.magnitude_ok
+    }
+
+    /// Fault to feed to the health monitor if the results disagree
+    pub fn fault(&self) -> Option<Fault> {
+        if self.is_ok() {
+            None
+        } else {
+            Some(Fault::AngleMismatch)
+        }
+    }
+}
+
+fn angle_difference(a: f32, b: f32) -> f32 {
+    let diff = libm::fabsf(a - b) % 360.0;
+    if diff > 180.0 {
+        360.0 - diff
+    } else {
+        diff
+    }
+}
+
+impl<SPI, CS, B, E, PE> Tmag5170<SPI, CS, B>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    B: Backoff,
+{
+    /// Reads the channels selected by the AngleEn of the applied SensorConfig together with
+    /// ANGLE_RESULT and MAGNITUDE_RESULT, and cross-checks them.
+    /// Results of one conversion are read only if no conversion completes during the reads,
+    /// e.g. in standby mode with a triggered conversion
+    pub fn check_angle(
+        &mut self,
+        tolerance: AngleTolerance,
+    ) -> Result<AngleCheck, ExtError<E, PE>> {
+        let angle_en = self.config().sensor.angle_en();
+        if angle_en == AngleEn::NoAngle {
+            return Err(ExtError::AngleDisabled);
+        }
+
+        let (x, y, z) = self.read_mag_registers()?;
+        let (angle, magnitude) = self.read_angle_registers()?;
+
+        let (first, second) = match angle_en {
+            AngleEn::Xy => (x, y),
+            AngleEn::Yz => (y, z),
+            _ => (z, x),
+        };
+
+        Ok(AngleCheck::compare(
+            first,
+            second,
+            angle as u16,
+            magnitude as u16,
+            tolerance,
+        ))
+    }
+}
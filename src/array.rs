@@ -0,0 +1,167 @@
+use core::convert::Infallible;
+
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::alert_config::AlertConfig;
+use crate::bus::{BusMutex, NoCs, SharedSpi, SharedSpiError};
+use crate::device_config::DeviceConfig;
+use crate::sensor_config::SensorConfig;
+use crate::system_config::SystemConfig;
+use crate::validation::{ConfigSet, Issues, ValidationMode};
+use crate::{ExtError, Tmag5170};
+
+/// Driver of one sensor of the array
+pub type ArraySensor<'a, M, CS> = Tmag5170<SharedSpi<'a, M, CS>, NoCs>;
+
+/// Error of one sensor of the array, NCS pin errors are returned as ExtError::Pin
+pub type ArrayError<E, PE> = ExtError<E, PE>;
+
+/// Error of a sensor driver on the shared bus
+type SensorError<E, PE> = ExtError<SharedSpiError<E, PE>, Infallible>;
+
+/// Moves the NCS pin errors of the shared bus to ExtError::Pin
+fn array_error<E, PE>(error: SensorError<E, PE>) -> ArrayError<E, PE> {
+    match error {
+        ExtError::Spi(SharedSpiError::Spi(e)) => ExtError::Spi(e),
+        ExtError::Spi(SharedSpiError::Pin(e)) => ExtError::Pin(e),
+        ExtError::Pin(never) => match never {},
+        ExtError::Crc => ExtError::Crc,
+        ExtError::Frame(status) => ExtError::Frame(status),
+        ExtError::Timeout => ExtError::Timeout,
+        ExtError::InvalidConfig(issue) => ExtError::InvalidConfig(issue),
+        ExtError::AngleDisabled => ExtError::AngleDisabled,
+        ExtError::SpecialDataType => ExtError::SpecialDataType,
+        ExtError::NotPresent => ExtError::NotPresent,
+        ExtError::WrongDevice { register, value } => ExtError::WrongDevice { register, value },
+        ExtError::Mismatch {
+            register,
+            expected,
+            actual,
+            bits,
+        } => ExtError::Mismatch {
+            register,
+            expected,
+            actual,
+            bits,
+        },
+    }
+}
+
+/// One result per sensor of the array
+pub type ArrayResult<T, E, PE, const N: usize> = [Result<T, ArrayError<E, PE>>; N];
+
+/// Array of TMAG5170-Q1 sensors on one shared SPI bus with individual NCS pins.
+/// Every operation returns one result per sensor, in the order of the NCS pins
+pub struct Tmag5170Array<'a, M, CS, const N: usize> {
+    bus: &'a M,
+    sensors: [ArraySensor<'a, M, CS>; N],
+}
+
+impl<'a, M, CS, E, PE, const N: usize> Tmag5170Array<'a, M, CS, N>
+where
+    M: BusMutex,
+    M::Bus: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+{
+    /// Creates a new sensor array from a shared SPI bus and NCS pins
+    pub fn new(bus: &'a M, cs: [CS; N]) -> Self {
+        let sensors = cs.map(|cs| Tmag5170::new_shared(bus, cs));
+        Tmag5170Array { bus, sensors }
+    }
+
+    /// Releases the NCS pins
+    pub fn free(self) -> [CS; N] {
+        self.sensors.map(|sensor| sensor.free().0.free())
+    }
+
+    /// Drivers of the sensors, e.g. to set their retry policies
+    pub fn sensors_mut(&mut self) -> &mut [ArraySensor<'a, M, CS>; N] {
+        &mut self.sensors
+    }
+
+    /// Number of sensors in the array
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns true if the array has no sensors
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    fn each<T>(
+        &mut self,
+        mut op: impl FnMut(&mut ArraySensor<'a, M, CS>) -> Result<T, SensorError<E, PE>>,
+    ) -> ArrayResult<T, E, PE, N> {
+        let sensors = &mut self.sensors;
+        core::array::from_fn(|idx| op(&mut sensors[idx]).map_err(array_error))
+    }
+
+    /// Apply TMAG5170-Q1 DeviceConfig to all sensors
+    pub fn apply_device_config(&mut self, config: DeviceConfig) -> ArrayResult<(), E, PE, N> {
+        self.each(|sensor| sensor.apply_device_config(config))
+    }
+
+    /// Apply TMAG5170-Q1 SensorConfig to all sensors
+    pub fn apply_sensor_config(&mut self, config: SensorConfig) -> ArrayResult<(), E, PE, N> {
+        self.each(|sensor| sensor.apply_sensor_config(config))
+    }
+
+    /// Apply TMAG5170-Q1 SystemConfig to all sensors
+    pub fn apply_system_config(&mut self, config: SystemConfig) -> ArrayResult<(), E, PE, N> {
+        self.each(|sensor| sensor.apply_system_config(config))
+    }
+
+    /// Apply TMAG5170-Q1 AlertConfig to all sensors
+    pub fn apply_alert_config(&mut self, config: AlertConfig) -> ArrayResult<(), E, PE, N> {
+        self.each(|sensor| sensor.apply_alert_config(config))
+    }
+
+    /// Validate and apply all configuration registers to all sensors.
+    /// DEVICE_CONFIG is written last, so conversions start with the rest of the configuration in place
+    pub fn apply_config(
+        &mut self,
+        config: ConfigSet,
+        mode: ValidationMode,
+    ) -> ArrayResult<Issues, E, PE, N> {
+        self.each(|sensor| sensor.apply_config(config, mode))
+    }
+
+    /// Starts a conversion on every sensor with back-to-back SPI commands
+    pub fn conv_start_all(&mut self) -> ArrayResult<(), E, PE, N> {
+        self.each(|sensor| sensor.conv_start())
+    }
+
+    /// Starts a conversion on every sensor at once by pulsing all NCS pins together while the bus
+    /// is locked. Requires TriggerMode::Cs
+    pub fn sync_trigger(&mut self) -> ArrayResult<(), E, PE, N> {
+        let sensors = &mut self.sensors;
+        self.bus.lock(|_| {
+            let low: [Result<(), PE>; N] =
+                core::array::from_fn(|idx| sensors[idx].spi.cs_mut().set_low());
+            let mut low = IntoIterator::into_iter(low);
+
+            core::array::from_fn(|idx| {
+                let low = low.next().unwrap_or(Ok(()));
+                let high = sensors[idx].spi.cs_mut().set_high();
+                low.and(high).map_err(ExtError::Pin)
+            })
+        })
+    }
+
+    /// Reads magnetic registers of every sensor in raw format. Returns (x,y,z) per sensor
+    pub fn read_mag_registers(&mut self) -> ArrayResult<(i16, i16, i16), E, PE, N> {
+        self.each(|sensor| sensor.read_mag_registers())
+    }
+
+    /// Reads temp register of every sensor in raw format
+    pub fn read_temp_register(&mut self) -> ArrayResult<i16, E, PE, N> {
+        self.each(|sensor| sensor.read_temp_register())
+    }
+
+    /// Reads angle and magnitude of every sensor as special read. Returns (angle,magnitude) per sensor
+    pub fn read_am(&mut self) -> ArrayResult<(u16, u16), E, PE, N> {
+        self.each(|sensor| sensor.read_am())
+    }
+}
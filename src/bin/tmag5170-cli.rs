@@ -0,0 +1,433 @@
+//! Inspects and configures a TMAG5170-Q1 over Linux spidev, or a simulated device
+//!
+//! Run `tmag5170-cli help` for the usage
+
+use std::fmt::{Debug, Display};
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+use linux_embedded_hal::spidev::{SpiModeFlags, SpidevOptions};
+use linux_embedded_hal::sysfs_gpio::Direction;
+use linux_embedded_hal::{Spidev, SysfsPin};
+
+use tmag5170::alert_config::AlertConfig;
+use tmag5170::decode;
+use tmag5170::device_config::{DeviceConfig, OperatingMode};
+use tmag5170::dump::RegisterDump;
+use tmag5170::sensor_config::{MagChEn, Range, SensorConfig};
+use tmag5170::sim::SimDevice;
+use tmag5170::system_config::SystemConfig;
+use tmag5170::test_config::Version;
+use tmag5170::validation::{ConfigSet, ValidationMode};
+use tmag5170::{Register, Tmag5170};
+
+const USAGE: &str = "\
+Usage: tmag5170-cli <backend> <command>
+
+Backends:
+  --spi <path> --cs-gpio <n> [--speed <hz>]  spidev device and sysfs GPIO used as CS
+  --sim[=a1|a2] [--field <x,y,z>]            simulated device with a static field in mT
+
+Options:
+  --config <file>                            run `write <file>` before the command
+
+Commands:
+  probe                                      check the device and print its version
+  dump                                       print all registers with decoded fields
+  restore <file>                             write back the writable bits of a dump
+  write <file>                               write config registers, one `NAME = VALUE` per line
+  stream [--count <n>] [--interval-ms <ms>]  print measurements in mT and degC
+  diag                                       run the self-test";
+
+/// Conversions are polled for this long before giving up
+const TIMEOUT_US: u32 = 100_000;
+
+enum Backend {
+    Linux {
+        spi: String,
+        cs: u64,
+        speed: u32,
+    },
+    Sim {
+        version: Version,
+        field: (f32, f32, f32),
+    },
+}
+
+enum Command {
+    Probe,
+    Dump,
+    Write(String),
+    Restore(String),
+    Stream {
+        count: Option<u32>,
+        interval_ms: u64,
+    },
+    Diag,
+}
+
+struct Args {
+    backend: Backend,
+    config: Option<String>,
+    command: Command,
+}
+
+struct StdDelay;
+
+impl DelayUs<u32> for StdDelay {
+    fn delay_us(&mut self, us: u32) {
+        thread::sleep(Duration::from_micros(us as u64));
+    }
+}
+
+fn main() {
+    let Args {
+        backend,
+        config,
+        command,
+    } = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("tmag5170-cli: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let res = match backend {
+        Backend::Linux { spi, cs, speed } => open_linux(&spi, cs, speed)
+            .and_then(|(spi, cs)| run(Tmag5170::new(spi, cs), config, command)),
+        Backend::Sim { version, field } => {
+            let sim = SimDevice::new(version);
+            sim.set_field(field.0, field.1, field.2);
+            run(Tmag5170::new(sim.spi(), sim.cs()), config, command)
+        }
+    };
+    if let Err(e) = res {
+        eprintln!("tmag5170-cli: {}", e);
+        process::exit(1);
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut spi = None;
+    let mut cs = None;
+    let mut speed = 1_000_000;
+    let mut sim = None;
+    let mut field = (0.0, 0.0, 0.0);
+    let mut count = None;
+    let mut interval_ms = 100;
+    let mut config = None;
+    let mut positional = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.as_str() {
+            "--spi" => spi = Some(value("--spi")?),
+            "--cs-gpio" => cs = Some(number(&value("--cs-gpio")?)?),
+            "--speed" => speed = number(&value("--speed")?)?,
+            "--sim" | "--sim=a1" => sim = Some(Version::A1),
+            "--sim=a2" => sim = Some(Version::A2),
+            "--field" => field = parse_field(&value("--field")?)?,
+            "--config" => config = Some(value("--config")?),
+            "--count" => count = Some(number(&value("--count")?)?),
+            "--interval-ms" => interval_ms = number(&value("--interval-ms")?)?,
+            "help" | "--help" | "-h" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            _ => positional.push(arg),
+        }
+    }
+
+    let backend = match (sim, spi, cs) {
+        (Some(version), None, None) => Backend::Sim { version, field },
+        (None, Some(spi), Some(cs)) => Backend::Linux { spi, cs, speed },
+        (None, Some(_), None) => return Err("--spi needs --cs-gpio".into()),
+        (None, None, _) => return Err("no backend, use --spi or --sim".into()),
+        _ => return Err("--sim can't be combined with --spi".into()),
+    };
+
+    let mut positional = positional.into_iter();
+    let command = match positional.next().as_deref() {
+        Some("probe") => Command::Probe,
+        Some("dump") => Command::Dump,
+        Some("write") => Command::Write(positional.next().ok_or("write needs a file")?),
+        Some("restore") => Command::Restore(positional.next().ok_or("restore needs a file")?),
+        Some("stream") => Command::Stream { count, interval_ms },
+        Some("diag") => Command::Diag,
+        Some(other) => return Err(format!("unknown command {}", other)),
+        None => return Err("no command".into()),
+    };
+    if let Some(extra) = positional.next() {
+        return Err(format!("unexpected argument {}", extra));
+    }
+
+    Ok(Args {
+        backend,
+        config,
+        command,
+    })
+}
+
+fn number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid number {}", value))
+}
+
+fn parse_field(value: &str) -> Result<(f32, f32, f32), String> {
+    let parts: Vec<f32> = value
+        .split(',')
+        .map(|v| number(v.trim()))
+        .collect::<Result<_, _>>()?;
+    match parts[..] {
+        [x, y, z] => Ok((x, y, z)),
+        _ => Err(format!("field {} is not x,y,z", value)),
+    }
+}
+
+fn open_linux(path: &str, cs: u64, speed: u32) -> Result<(Spidev, SysfsPin), String> {
+    let mut spi = Spidev::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    let options = SpidevOptions::new()
+        .bits_per_word(8)
+        .max_speed_hz(speed)
+        .mode(SpiModeFlags::SPI_MODE_0 | SpiModeFlags::SPI_NO_CS)
+        .build();
+    spi.configure(&options).map_err(err)?;
+
+    let pin = SysfsPin::new(cs);
+    pin.export().map_err(err)?;
+    pin.set_direction(Direction::High).map_err(err)?;
+
+    Ok((spi, pin))
+}
+
+fn err<T: Display>(e: T) -> String {
+    e.to_string()
+}
+
+fn run<SPI, CS, E, PE>(
+    mut tmag5170: Tmag5170<SPI, CS>,
+    config: Option<String>,
+    command: Command,
+) -> Result<(), String>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    E: Debug,
+    PE: Debug,
+{
+    if let Some(path) = config {
+        write_config(&mut tmag5170, &path)?;
+    }
+
+    match command {
+        Command::Probe => {
+            let version = tmag5170.probe().map_err(err)?;
+            println!("TMAG5170-Q1 {:?}", version);
+        }
+        Command::Dump => print!("{}", tmag5170.dump_registers().map_err(err)?.to_text()),
+        Command::Restore(path) => {
+            let text = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+            let dump = RegisterDump::from_text(&text).map_err(|e| format!("{}: {}", path, e))?;
+            tmag5170.set_write_verify(true);
+            tmag5170.restore_registers(&dump).map_err(err)?;
+        }
+        Command::Write(path) => write_config(&mut tmag5170, &path)?,
+        Command::Stream { count, interval_ms } => stream(&mut tmag5170, count, interval_ms)?,
+        Command::Diag => {
+            let report = tmag5170.self_test(&mut StdDelay, TIMEOUT_US).map_err(err)?;
+            println!("version: {:?}", report.version());
+            println!(
+                "AFE_STATUS {}",
+                decode::fields(Register::AFE_STATUS, report.afe.to_u16())
+            );
+            println!(
+                "SYS_STATUS {}",
+                decode::fields(Register::SYS_STATUS, report.sys.to_u16())
+            );
+            for mechanism in report.diagnostics.passed() {
+                println!("pass {:?}", mechanism);
+            }
+            for mechanism in report.diagnostics.failed() {
+                println!("FAIL {:?}", mechanism);
+            }
+            if !report.is_ok() {
+                return Err("self-test failed".into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Registers missing in the file keep their current value. DEVICE_CONFIG is written last
+fn write_config<SPI, CS, E, PE>(tmag5170: &mut Tmag5170<SPI, CS>, path: &str) -> Result<(), String>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    E: Debug,
+    PE: Debug,
+{
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+
+    let mut read = |reg| tmag5170.read_raw_register(reg).map_err(err);
+    let mut values = [
+        read(Register::DEVICE_CONFIG)?,
+        read(Register::SENSOR_CONFIG)?,
+        read(Register::SYSTEM_CONFIG)?,
+        read(Register::ALERT_CONFIG)?,
+    ];
+
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let at = |msg: &str| format!("{}:{}: {}", path, idx + 1, msg);
+        let (name, value) = match line.split_once('=') {
+            Some((name, value)) => (name.trim(), value.trim()),
+            None => return Err(at("expected NAME = VALUE")),
+        };
+        let reg = Register::ALL
+            .iter()
+            .copied()
+            .find(|reg| format!("{:?}", reg).eq_ignore_ascii_case(name))
+            .ok_or_else(|| at("unknown register"))?;
+        let value = parse_value(value).ok_or_else(|| at("invalid value"))?;
+        match reg {
+            Register::DEVICE_CONFIG => values[0] = value,
+            Register::SENSOR_CONFIG => values[1] = value,
+            Register::SYSTEM_CONFIG => values[2] = value,
+            Register::ALERT_CONFIG => values[3] = value,
+            _ => return Err(at("not a configuration register")),
+        }
+    }
+
+    let config = ConfigSet {
+        device: DeviceConfig::form_u16(values[0]),
+        sensor: SensorConfig::form_u16(values[1]),
+        system: SystemConfig::form_u16(values[2]),
+        alert: AlertConfig::form_u16(values[3]),
+    };
+    tmag5170.set_write_verify(true);
+    let issues = tmag5170
+        .apply_config(config, ValidationMode::Warn)
+        .map_err(err)?;
+    for issue in issues.iter() {
+        eprintln!("warning: {:?}", issue);
+    }
+
+    Ok(())
+}
+
+fn parse_value(value: &str) -> Option<u16> {
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u16::from_str_radix(&hex.replace('_', ""), 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Devices in configuration or sleep modes are put into standby and triggered by command
+fn stream<SPI, CS, E, PE>(
+    tmag5170: &mut Tmag5170<SPI, CS>,
+    count: Option<u32>,
+    interval_ms: u64,
+) -> Result<(), String>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    E: Debug,
+    PE: Debug,
+{
+    let version = tmag5170
+        .read_test_config()
+        .map_err(err)?
+        .ver()
+        .ok_or("unknown device version")?;
+    let sensor = SensorConfig::form_u16(
+        tmag5170
+            .read_raw_register(Register::SENSOR_CONFIG)
+            .map_err(err)?,
+    );
+    let mut device = DeviceConfig::form_u16(
+        tmag5170
+            .read_raw_register(Register::DEVICE_CONFIG)
+            .map_err(err)?,
+    );
+
+    if sensor.mag_ch_en() == MagChEn::Off && !device.t_en() {
+        return Err("no channel enabled, set MAG_CH_EN or T_CH_EN first".into());
+    }
+
+    let continuous = match device.operating_mode() {
+        Some(OperatingMode::Active) | Some(OperatingMode::WakeupAndSleep) => true,
+        Some(OperatingMode::Standby) | Some(OperatingMode::ActiveTrigger) => false,
+        _ => {
+            device = device.set_operating_mode(OperatingMode::Standby);
+            tmag5170.apply_device_config(device).map_err(err)?;
+            false
+        }
+    };
+
+    let scale = |range: Option<Range>| -> Result<f32, String> {
+        let range = range.ok_or("reserved range")?;
+        Ok(range.full_scale(version) / 32768.0)
+    };
+    let (sx, sy, sz) = (
+        scale(sensor.x_range())?,
+        scale(sensor.y_range())?,
+        scale(sensor.z_range())?,
+    );
+
+    let mut n = 0;
+    while count.is_none() || count > Some(n) {
+        if !continuous {
+            // RDY may be left over from the previous conversion
+            let set_count = tmag5170.read_conv_status().map_err(err)?.set_count();
+            tmag5170.conv_start().map_err(err)?;
+            let mut waited_us = 0;
+            loop {
+                let conv = tmag5170.read_conv_status().map_err(err)?;
+                if conv.rdy() && conv.set_count() != set_count {
+                    break;
+                }
+                if waited_us >= TIMEOUT_US {
+                    return Err("conversion timed out".into());
+                }
+                StdDelay.delay_us(100);
+                waited_us += 100;
+            }
+        }
+        let (x, y, z) = tmag5170.read_mag_registers().map_err(err)?;
+        let mut line = String::new();
+        let mag = sensor.mag_ch_en();
+        for (enabled, name, code, scale) in [
+            (mag.enables_x(), "x", x, sx),
+            (mag.enables_y(), "y", y, sy),
+            (mag.enables_z(), "z", z, sz),
+        ] {
+            if enabled {
+                line += &format!("{}={:9.3} mT ", name, code as f32 * scale);
+            }
+        }
+        if device.t_en() {
+            let t = tmag5170.read_temp_register().map_err(err)? as u16;
+            line += &format!("t={:7.2} degC", tmag5170::temp_celsius(t));
+        }
+        println!("{}", line.trim_end());
+
+        n += 1;
+        thread::sleep(Duration::from_millis(interval_ms));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! Decodes TMAG5170-Q1 SPI traffic from a logic analyzer CSV export
+//!
+//! Usage: `tmag5170-decode [capture.csv]`, reads stdin without a file
+
+use std::io::{self, Read};
+use std::process;
+
+use tmag5170::decode::{parse_csv, Decoder};
+
+fn main() {
+    let mut text = String::new();
+    let res = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(&path).map(|t| text = t),
+        None => io::stdin().read_to_string(&mut text).map(|_| ()),
+    };
+    if let Err(e) = res {
+        eprintln!("tmag5170-decode: {}", e);
+        process::exit(1);
+    }
+
+    let capture = match parse_csv(&text) {
+        Ok(capture) => capture,
+        Err(e) => {
+            eprintln!("tmag5170-decode: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let mut decoder = Decoder::new();
+    for frame in &capture.frames {
+        let decoded = decoder.decode(&frame.sdi, &frame.sdo);
+        match &frame.time {
+            Some(time) => println!("{:>14} {}", time, decoded),
+            None => println!("{}", decoded),
+        }
+    }
+    if capture.dropped_bytes != 0 {
+        eprintln!(
+            "tmag5170-decode: {} bytes outside complete frames",
+            capture.dropped_bytes
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::retry::NoBackoff;
+use crate::Tmag5170;
+
+/// Gives exclusive access to a SPI bus shared between several drivers
+pub trait BusMutex {
+    /// SPI bus type
+    type Bus;
+
+    /// Runs `f` with the bus locked
+    fn lock<R>(&self, f: impl FnOnce(&mut Self::Bus) -> R) -> R;
+}
+
+/// Single context sharing. Panics if the bus is accessed re-entrantly
+impl<T> BusMutex for RefCell<T> {
+    type Bus = T;
+
+    fn lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.borrow_mut())
+    }
+}
+
+/// Sharing between interrupt contexts
+#[cfg(feature = "critical-section")]
+impl<T> BusMutex for critical_section::Mutex<RefCell<T>> {
+    type Bus = T;
+
+    fn lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.borrow_ref_mut(cs)))
+    }
+}
+
+/// Sharing between threads
+#[cfg(feature = "std")]
+impl<T> BusMutex for std::sync::Mutex<T> {
+    type Bus = T;
+
+    fn lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let mut bus = self.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut bus)
+    }
+}
+
+/// Shared bus error type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SharedSpiError<E, PE> {
+    /// Generic SPI error
+    Spi(E),
+
+    /// Chip select pin error
+    Pin(PE),
+}
+
+/// One device on a shared SPI bus. The bus stays locked while the chip select is asserted
+pub struct SharedSpi<'a, M, CS> {
+    bus: &'a M,
+    cs: CS,
+}
+
+impl<'a, M, CS> SharedSpi<'a, M, CS> {
+    /// Creates device from a shared bus and its NCS pin
+    pub fn new(bus: &'a M, cs: CS) -> Self {
+        SharedSpi { bus, cs }
+    }
+
+    /// Releases the NCS pin
+    pub fn free(self) -> CS {
+        self.cs
+    }
+
+    pub(crate) fn cs_mut(&mut self) -> &mut CS {
+        &mut self.cs
+    }
+}
+
+impl<'a, M, CS, E, PE> Transfer<u8> for SharedSpi<'a, M, CS>
+where
+    M: BusMutex,
+    M::Bus: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+{
+    type Error = SharedSpiError<E, PE>;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Self::Error> {
+        let cs = &mut self.cs;
+        self.bus.lock(|spi| {
+            cs.set_low().map_err(SharedSpiError::Pin)?;
+            let res = spi.transfer(words).map(|_| ());
+            let cs_res = cs.set_high();
+            res.map_err(SharedSpiError::Spi)?;
+            cs_res.map_err(SharedSpiError::Pin)
+        })?;
+
+        Ok(words)
+    }
+}
+
+/// Placeholder NCS pin for devices whose chip select is driven by [`SharedSpi`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoCs;
+
+impl OutputPin for NoCs {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+impl<'a, M, CS, E, PE> Tmag5170<SharedSpi<'a, M, CS>, NoCs, NoBackoff>
+where
+    M: BusMutex,
+    M::Bus: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+{
+    /// Creates a new driver on a shared SPI bus
+    pub fn new_shared(bus: &'a M, cs: CS) -> Self {
+        Tmag5170::new(SharedSpi::new(bus, cs), NoCs)
+    }
+}
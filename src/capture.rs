@@ -0,0 +1,421 @@
+use core::cell::RefCell;
+use core::fmt;
+use std::rc::Rc;
+use std::time::Instant;
+use std::vec::Vec;
+
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+const MAGIC: &[u8; 4] = b"T5C2";
+
+const TAG_CS_LOW: u8 = 0;
+const TAG_CS_HIGH: u8 = 1;
+const TAG_TRANSFER: u8 = 2;
+const TAG_TIME: u8 = 0x80;
+
+/// One captured bus event
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// CS driven low
+    CsLow,
+    /// CS driven high
+    CsHigh,
+    /// SPI transfer, bytes sent on SDI and received on SDO
+    Transfer {
+        /// Bytes sent by the driver
+        sdi: Vec<u8>,
+        /// Bytes received from the device
+        sdo: Vec<u8>,
+    },
+}
+
+/// Captured event with its optional timestamp
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    /// Bus event
+    pub event: Event,
+    /// Microseconds since the recorder was created, wraps after ~71 minutes
+    pub time_us: Option<u32>,
+}
+
+/// Ordered list of captured events
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Log {
+    records: Vec<Record>,
+}
+
+impl Log {
+    /// Creates empty log
+    pub fn new() -> Self {
+        Log::default()
+    }
+
+    /// Appends an event
+    pub fn push(&mut self, event: Event, time_us: Option<u32>) {
+        self.records.push(Record { event, time_us });
+    }
+
+    /// Captured events in bus order
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Number of captured events
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns true when nothing was captured
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Encodes the log. Each event is a tag byte, an optional little endian u32
+    /// timestamp and, for transfers, the LEB128 encoded length followed by the SDI and SDO bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        for record in &self.records {
+            let tag = match record.event {
+                Event::CsLow => TAG_CS_LOW,
+                Event::CsHigh => TAG_CS_HIGH,
+                Event::Transfer { .. } => TAG_TRANSFER,
+            };
+            match record.time_us {
+                Some(time) => {
+                    bytes.push(tag | TAG_TIME);
+                    bytes.extend_from_slice(&time.to_le_bytes());
+                }
+                None => bytes.push(tag),
+            }
+            if let Event::Transfer { sdi, sdo } = &record.event {
+                write_len(&mut bytes, sdi.len());
+                bytes.extend_from_slice(sdi);
+                bytes.extend_from_slice(sdo);
+            }
+        }
+        bytes
+    }
+
+    /// Decodes a log produced by [`Log::to_bytes`], returns None if it is malformed
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut bytes = bytes.strip_prefix(&MAGIC[..])?;
+        let mut log = Log::new();
+        while let Some((&tag, rest)) = bytes.split_first() {
+            bytes = rest;
+            let time_us = if tag & TAG_TIME != 0 {
+                let time = take(&mut bytes, 4)?;
+                Some(u32::from_le_bytes([time[0], time[1], time[2], time[3]]))
+            } else {
+                None
+            };
+            let event = match tag & !TAG_TIME {
+                TAG_CS_LOW => Event::CsLow,
+                TAG_CS_HIGH => Event::CsHigh,
+                TAG_TRANSFER => {
+                    let len = read_len(&mut bytes)?;
+                    let sdi = take(&mut bytes, len)?.to_vec();
+                    let sdo = take(&mut bytes, len)?.to_vec();
+                    Event::Transfer { sdi, sdo }
+                }
+                _ => return None,
+            };
+            log.push(event, time_us);
+        }
+        Some(log)
+    }
+}
+
+fn write_len(bytes: &mut Vec<u8>, mut len: usize) {
+    while len >= 0x80 {
+        bytes.push(len as u8 | 0x80);
+        len >>= 7;
+    }
+    bytes.push(len as u8);
+}
+
+fn read_len(bytes: &mut &[u8]) -> Option<usize> {
+    let mut len: usize = 0;
+    let mut shift = 0;
+    loop {
+        let byte = take(bytes, 1)?[0];
+        let bits = usize::from(byte & 0x7f);
+        if shift >= usize::BITS || bits.checked_shl(shift)? >> shift != bits {
+            return None;
+        }
+        len |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Some(len);
+        }
+        shift += 7;
+    }
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if bytes.len() < len {
+        return None;
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Some(head)
+}
+
+/// Captures the traffic of a wrapped SPI bus and CS pin into a [`Log`].
+/// Clones share the same log
+#[derive(Clone)]
+pub struct Recorder {
+    state: Rc<RefCell<RecorderState>>,
+}
+
+struct RecorderState {
+    log: Log,
+    start: Option<Instant>,
+}
+
+impl RecorderState {
+    fn push(&mut self, event: Event) {
+        let time_us = self.start.map(|start| start.elapsed().as_micros() as u32);
+        self.log.push(event, time_us);
+    }
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Recorder {
+    /// Creates recorder without timestamps
+    pub fn new() -> Self {
+        Recorder {
+            state: Rc::new(RefCell::new(RecorderState {
+                log: Log::new(),
+                start: None,
+            })),
+        }
+    }
+
+    /// Enables timestamps, counted from this call
+    pub fn set_timestamps(self, enabled: bool) -> Self {
+        self.state.borrow_mut().start = if enabled { Some(Instant::now()) } else { None };
+        self
+    }
+
+    /// Wraps an SPI bus
+    pub fn spi<SPI>(&self, spi: SPI) -> RecordingSpi<SPI> {
+        RecordingSpi {
+            inner: spi,
+            state: self.state.clone(),
+        }
+    }
+
+    /// Wraps a chip select pin
+    pub fn cs<CS>(&self, cs: CS) -> RecordingCs<CS> {
+        RecordingCs {
+            inner: cs,
+            state: self.state.clone(),
+        }
+    }
+
+    /// Copy of the events captured so far
+    pub fn log(&self) -> Log {
+        self.state.borrow().log.clone()
+    }
+
+    /// Drops the events captured so far
+    pub fn clear(&self) {
+        self.state.borrow_mut().log = Log::new();
+    }
+}
+
+/// SPI bus wrapped by a [`Recorder`]
+pub struct RecordingSpi<SPI> {
+    inner: SPI,
+    state: Rc<RefCell<RecorderState>>,
+}
+
+impl<SPI> RecordingSpi<SPI> {
+    /// Destroys the wrapper and returns the bus
+    pub fn free(self) -> SPI {
+        self.inner
+    }
+}
+
+impl<SPI: Transfer<u8>> Transfer<u8> for RecordingSpi<SPI> {
+    type Error = SPI::Error;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], SPI::Error> {
+        let sdi = words.to_vec();
+        let sdo = self.inner.transfer(words)?;
+        self.state.borrow_mut().push(Event::Transfer {
+            sdi,
+            sdo: sdo.to_vec(),
+        });
+        Ok(sdo)
+    }
+}
+
+/// Chip select pin wrapped by a [`Recorder`]
+pub struct RecordingCs<CS> {
+    inner: CS,
+    state: Rc<RefCell<RecorderState>>,
+}
+
+impl<CS> RecordingCs<CS> {
+    /// Destroys the wrapper and returns the pin
+    pub fn free(self) -> CS {
+        self.inner
+    }
+}
+
+impl<CS: OutputPin> OutputPin for RecordingCs<CS> {
+    type Error = CS::Error;
+
+    fn set_low(&mut self) -> Result<(), CS::Error> {
+        self.inner.set_low()?;
+        self.state.borrow_mut().push(Event::CsLow);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), CS::Error> {
+        self.inner.set_high()?;
+        self.state.borrow_mut().push(Event::CsHigh);
+        Ok(())
+    }
+}
+
+/// Bus activity that does not match the replayed log
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplayError {
+    /// Index of the record in the log
+    pub index: usize,
+    /// Recorded event, None when the log is exhausted
+    pub expected: Option<Event>,
+    /// Event issued by the driver, SDO is empty for transfers
+    pub actual: Event,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.expected {
+            Some(expected) => write!(
+                f,
+                "replay diverged at record {}: expected {:?}, got {:?}",
+                self.index, expected, self.actual
+            ),
+            None => write!(f, "replay log exhausted, got {:?}", self.actual),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Plays a [`Log`] back as SPI bus and CS pin. CS edges and SDI bytes must match
+/// the log, SDO bytes are taken from it. Timestamps are ignored.
+/// Clones share the same position
+#[derive(Clone)]
+pub struct Replayer {
+    state: Rc<RefCell<ReplayerState>>,
+}
+
+struct ReplayerState {
+    log: Log,
+    position: usize,
+}
+
+impl ReplayerState {
+    fn next(&mut self, actual: Event) -> Result<Option<Vec<u8>>, ReplayError> {
+        let index = self.position;
+        let expected = self.log.records.get(index).map(|record| &record.event);
+        let sdo = match (expected, &actual) {
+            (Some(Event::CsLow), Event::CsLow) | (Some(Event::CsHigh), Event::CsHigh) => None,
+            (
+                Some(Event::Transfer { sdi, sdo }),
+                Event::Transfer {
+                    sdi: actual_sdi, ..
+                },
+            ) if sdi == actual_sdi => Some(sdo.clone()),
+            _ => {
+                return Err(ReplayError {
+                    index,
+                    expected: expected.cloned(),
+                    actual,
+                })
+            }
+        };
+        self.position += 1;
+        Ok(sdo)
+    }
+}
+
+impl Replayer {
+    /// Creates replayer positioned at the first record
+    pub fn new(log: Log) -> Self {
+        Replayer {
+            state: Rc::new(RefCell::new(ReplayerState { log, position: 0 })),
+        }
+    }
+
+    /// SPI bus replaying the log
+    pub fn spi(&self) -> ReplaySpi {
+        ReplaySpi {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Chip select pin replaying the log
+    pub fn cs(&self) -> ReplayCs {
+        ReplayCs {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Number of records not yet replayed
+    pub fn remaining(&self) -> usize {
+        let state = self.state.borrow();
+        state.log.len() - state.position
+    }
+
+    /// Returns true when the whole log was replayed
+    pub fn is_finished(&self) -> bool {
+        self.remaining() == 0
+    }
+}
+
+/// SPI bus of a [`Replayer`]
+pub struct ReplaySpi {
+    state: Rc<RefCell<ReplayerState>>,
+}
+
+impl Transfer<u8> for ReplaySpi {
+    type Error = ReplayError;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], ReplayError> {
+        let actual = Event::Transfer {
+            sdi: words.to_vec(),
+            sdo: Vec::new(),
+        };
+        if let Some(sdo) = self.state.borrow_mut().next(actual)? {
+            words.copy_from_slice(&sdo);
+        }
+        Ok(words)
+    }
+}
+
+/// Chip select pin of a [`Replayer`]
+pub struct ReplayCs {
+    state: Rc<RefCell<ReplayerState>>,
+}
+
+impl OutputPin for ReplayCs {
+    type Error = ReplayError;
+
+    fn set_low(&mut self) -> Result<(), ReplayError> {
+        self.state.borrow_mut().next(Event::CsLow).map(|_| ())
+    }
+
+    fn set_high(&mut self) -> Result<(), ReplayError> {
+        self.state.borrow_mut().next(Event::CsHigh).map(|_| ())
+    }
+}
@@ -0,0 +1,488 @@
+use core::fmt::{self, Debug, Write};
+use std::format;
+use std::string::String;
+use std::vec::Vec;
+
+use crc_all::Crc;
+
+use crate::alert_config::AlertConfig;
+use crate::angle_check;
+use crate::device_config::DeviceConfig;
+use crate::frame::{self, FrameStatus};
+use crate::sensor_config::SensorConfig;
+use crate::status::{AfeStatus, ConvStatus, SysStatus};
+use crate::system_config::{DataType, SystemConfig};
+use crate::test_config::TestConfig;
+use crate::Register;
+
+/// One 32-bit frame taken from a capture
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapturedFrame {
+    /// Time column of the first byte as exported, if the capture has one
+    pub time: Option<String>,
+    /// Bytes sent by the controller
+    pub sdi: [u8; 4],
+    /// Bytes sent by the device
+    pub sdo: [u8; 4],
+}
+
+/// Frames found in a logic analyzer capture
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Capture {
+    /// Complete frames in capture order
+    pub frames: Vec<CapturedFrame>,
+    /// Bytes of incomplete frames, e.g. at the start of the capture
+    pub dropped_bytes: usize,
+}
+
+/// Errors of the CSV capture parser
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CsvError {
+    /// Header has no column with this name
+    MissingColumn(&'static str),
+    /// Byte value in this line (1-based) can't be parsed
+    Value(usize),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::MissingColumn(name) => write!(f, "no {} column in the CSV header", name),
+            CsvError::Value(line) => write!(f, "invalid byte value in line {}", line),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// Parses an SPI analyzer export in CSV format. The header must name MOSI and MISO
+/// (or SDI and SDO) columns, a time column is optional. Frames are cut every 4 bytes
+/// and restarted when the packet ID changes or when an `enable`/`disable` row is met,
+/// as exported by Saleae Logic 1 and 2. Bytes may be hexadecimal with `0x` prefix,
+/// binary with `0b` prefix or decimal
+pub fn parse_csv(text: &str) -> Result<Capture, CsvError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+    let header: Vec<String> = match lines.next() {
+        Some((_, line)) => split(line).map(|c| c.to_lowercase()).collect(),
+        None => return Ok(Capture::default()),
+    };
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|c| names.iter().any(|name| c.contains(name)))
+    };
+    let mosi = column(&["mosi", "sdi"]).ok_or(CsvError::MissingColumn("MOSI"))?;
+    let miso = column(&["miso", "sdo"]).ok_or(CsvError::MissingColumn("MISO"))?;
+    let time = column(&["time"]);
+    let packet = column(&["packet"]);
+    let kind = header.iter().position(|c| c == "type");
+
+    let mut capture = Capture::default();
+    let mut pending: Vec<(u8, u8)> = Vec::new();
+    let mut pending_time = None;
+    let mut last_packet = None;
+    for (idx, line) in lines {
+        let cells: Vec<&str> = split(line).collect();
+        let cell = |col: Option<usize>| col.and_then(|c| cells.get(c)).copied().unwrap_or("");
+
+        let boundary = match (packet, kind) {
+            (Some(_), _) => {
+                let id = cell(packet);
+                let changed = matches!(last_packet, Some(last) if last != id);
+                last_packet = Some(id);
+                changed
+            }
+            (None, Some(_)) => matches!(cell(kind), "enable" | "disable"),
+            _ => false,
+        };
+        if boundary {
+            capture.dropped_bytes += pending.len();
+            pending.clear();
+        }
+
+        let (sdi, sdo) = (cell(Some(mosi)), cell(Some(miso)));
+        if sdi.is_empty() && sdo.is_empty() {
+            continue;
+        }
+        let byte = |value: &str| parse_byte(value).ok_or(CsvError::Value(idx + 1));
+        if pending.is_empty() {
+            pending_time = time.map(|t| String::from(cell(Some(t))));
+        }
+        pending.push((byte(sdi)?, byte(sdo)?));
+
+        if pending.len() == 4 {
+            let mut frame = CapturedFrame {
+                time: pending_time.take(),
+                sdi: [0; 4],
+                sdo: [0; 4],
+            };
+            for (i, &(sdi, sdo)) in pending.iter().enumerate() {
+                frame.sdi[i] = sdi;
+                frame.sdo[i] = sdo;
+            }
+            capture.frames.push(frame);
+            pending.clear();
+        }
+    }
+    capture.dropped_bytes += pending.len();
+
+    Ok(capture)
+}
+
+fn split(line: &str) -> impl Iterator<Item = &str> {
+    line.split(',').map(|c| c.trim().trim_matches('"'))
+}
+
+fn parse_byte(value: &str) -> Option<u8> {
+    let value = value.trim_matches('\'');
+    if let Some(hex) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .or_else(|| value.strip_prefix("\\x"))
+    {
+        u8::from_str_radix(hex, 16).ok()
+    } else if let Some(bin) = value.strip_prefix("0b") {
+        u8::from_str_radix(bin, 2).ok()
+    } else {
+        value.parse().ok()
+    }
+}
+
+/// Device response of a decoded frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Response {
+    /// Regular 32-bit frame with the 12 status bits and 16 data bits
+    Register {
+        /// STAT[11:0]
+        status: FrameStatus,
+        /// Data bits, the register value for reads
+        value: u16,
+    },
+    /// Special frame with STAT[11:8] and two 12-bit channels
+    Special {
+        /// STAT[11:8]
+        status: FrameStatus,
+        /// DATA_TYPE which selected the channels
+        data_type: DataType,
+        /// First channel
+        ch1: u16,
+        /// Second channel
+        ch2: u16,
+    },
+}
+
+/// Annotated 32-bit frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodedFrame {
+    /// Read (R/W bit set) or write request
+    pub read: bool,
+    /// Address bits of the request
+    pub addr: u8,
+    /// Addressed register, None for unknown addresses
+    pub register: Option<Register>,
+    /// Data bits of the request, the new register value for writes
+    pub value: u16,
+    /// CMD[3:0] bits of the request
+    pub cmd: u8,
+    /// CRC of the request is valid, or CRC is disabled
+    pub sdi_crc_ok: bool,
+    /// CRC of the response is valid, or CRC is disabled
+    pub sdo_crc_ok: bool,
+    /// Decoded response
+    pub response: Response,
+}
+
+/// Stateful frame decoder. It follows DATA_TYPE and CRC_DIS writes to know the
+/// response format of the next frames, so frames must be given in capture order
+pub struct Decoder {
+    crc: Crc<u8>,
+    data_type: DataType,
+    crc_dis: bool,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder {
+    /// Creates decoder assuming registers at their reset values
+    pub fn new() -> Self {
+        Decoder {
+            crc: Crc::<u8>::new(0x03, 4, 0x0f, 0x00, false),
+            data_type: DataType::Default,
+            crc_dis: false,
+        }
+    }
+
+    /// Decodes the next frame of the capture
+    pub fn decode(&mut self, sdi: &[u8; 4], sdo: &[u8; 4]) -> DecodedFrame {
+        let read = sdi[0] & 0x80 != 0;
+        let addr = sdi[0] & 0x7f;
+        let value = u16::from_be_bytes([sdi[1], sdi[2]]);
+        let sdi_crc_ok = self.crc_dis || frame::crc_ok(&mut self.crc, sdi);
+        let sdo_crc_ok = self.crc_dis || frame::crc_ok(&mut self.crc, sdo);
+
+        let response = match self.data_type {
+            DataType::Default => Response::Register {
+                status: frame::status(sdo),
+                value: frame::register_value(sdo),
+            },
+            data_type => {
+                let (ch1, ch2) = frame::special_values(sdo);
+                Response::Special {
+                    status: frame::special_status(sdo),
+                    data_type,
+                    ch1,
+                    ch2,
+                }
+            }
+        };
+
+        let register = Register::from_addr(addr);
+        if sdi_crc_ok && !read {
+            match register {
+                Some(Register::SYSTEM_CONFIG) => {
+                    self.data_type = SystemConfig::form_u16(value).data_type()
+                }
+                Some(Register::TEST_CONFIG) => self.crc_dis = TestConfig::form_u16(value).crc_dis(),
+                _ => (),
+            }
+        }
+
+        DecodedFrame {
+            read,
+            addr,
+            register,
+            value,
+            cmd: sdi[3] >> 4,
+            sdi_crc_ok,
+            sdo_crc_ok,
+            response,
+        }
+    }
+}
+
+impl fmt::Display for DecodedFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.register {
+            Some(reg) => format!("{:?}", reg),
+            None => format!("UNKNOWN({:#04x})", self.addr),
+        };
+        if self.read {
+            write!(f, "R {}", name)?;
+        } else {
+            write!(f, "W {} = {:#06x}", name, self.value)?;
+            write_fields(f, self.register, self.value)?;
+        }
+        if self.cmd != 0 {
+            write!(f, " cmd={:#03x}", self.cmd)?;
+            if self.cmd & 0b1 != 0 {
+                write!(f, " (conv start)")?;
+            }
+        }
+        write!(f, " crc={}", if self.sdi_crc_ok { "ok" } else { "BAD" })?;
+
+        write!(f, " | ")?;
+        match self.response {
+            Response::Register { status, value } => {
+                write!(f, "stat={:#05x}{}", status.to_u16(), flags(status))?;
+                if self.read {
+                    write!(f, " {:#06x}", value)?;
+                    write_fields(f, self.register, value)?;
+                }
+            }
+            Response::Special {
+                status,
+                data_type,
+                ch1,
+                ch2,
+            } => {
+                write!(f, "stat={:#05x}{}", status.to_u16(), flags(status))?;
+                write!(f, " {:?} ch1={:#05x} ch2={:#05x}", data_type, ch1, ch2)?;
+            }
+        }
+        write!(f, " crc={}", if self.sdo_crc_ok { "ok" } else { "BAD" })
+    }
+}
+
+fn write_fields(f: &mut fmt::Formatter<'_>, reg: Option<Register>, value: u16) -> fmt::Result {
+    match reg.map(|reg| fields(reg, value)) {
+        Some(fields) if !fields.is_empty() => write!(f, " {}", fields),
+        _ => Ok(()),
+    }
+}
+
+fn flags(status: FrameStatus) -> String {
+    let names = [
+        (status.prev_crc_error(), "prev_crc_error"),
+        (status.cfg_reset(), "cfg_reset"),
+        (status.sys_alert(), "sys_alert"),
+        (status.afe_alert(), "afe_alert"),
+        (status.x_stat(), "x"),
+        (status.y_stat(), "y"),
+        (status.z_stat(), "z"),
+        (status.t_stat(), "t"),
+    ];
+    let set: Vec<&str> = names
+        .iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, n)| *n)
+        .collect();
+    if set.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", set.join(" "))
+    }
+}
+
+fn opt<T: Debug>(value: Option<T>) -> String {
+    match value {
+        Some(v) => format!("{:?}", v),
+        None => String::from("reserved"),
+    }
+}
+
+/// Human readable fields of a register value, empty for registers without decoded fields
+pub fn fields(reg: Register, value: u16) -> String {
+    let mut s = String::new();
+    // Writing into a String can't fail
+    let _ = match reg {
+        Register::DEVICE_CONFIG => {
+            let c = DeviceConfig::form_u16(value);
+            write!(
+                s,
+                "[conv_avg={} mag_tempco={} operating_mode={} t_en={} t_rate={:?} t_limit_check_en={} t_comp_en={}]",
+                opt(c.conv_avg()),
+                opt(c.mag_tempco()),
+                opt(c.operating_mode()),
+                c.t_en(),
+                c.t_rate(),
+                c.t_limit_check_en(),
+                c.t_comp_en()
+            )
+        }
+        Register::SENSOR_CONFIG => {
+            let c = SensorConfig::form_u16(value);
+            write!(
+                s,
+                "[angle_en={:?} sleep_time={} mag_ch_en={:?} z_range={} y_range={} x_range={}]",
+                c.angle_en(),
+                opt(c.sleep_time()),
+                c.mag_ch_en(),
+                opt(c.z_range()),
+                opt(c.y_range()),
+                opt(c.x_range())
+            )
+        }
+        Register::SYSTEM_CONFIG => {
+            let c = SystemConfig::form_u16(value);
+            write!(
+                s,
+                "[diag_sel={:?} trigger_mode={} data_type={:?} diag_en={} t_z_limit_check={} t_y_limit_check={} t_x_limit_check={}]",
+                c.diag_sel(),
+                opt(c.trigger_mode()),
+                c.data_type(),
+                c.diag_en(),
+                c.t_z_limit_check(),
+                c.t_y_limit_check(),
+                c.t_x_limit_check()
+            )
+        }
+        Register::ALERT_CONFIG => {
+            let c = AlertConfig::form_u16(value);
+            write!(
+                s,
+                "[alert_latch={:?} alert_mode={:?} status_alrt={} rslt_alrt={} thrx_count={:?} t_thrx_alrt={} z_thrx_alrt={} y_thrx_alrt={} x_thrx_alrt={}]",
+                c.alert_latch(),
+                c.alert_mode(),
+                c.status_alrt_enable(),
+                c.rslt_alrt_enable(),
+                c.thrx_count(),
+                c.t_thrx_alrt_enable(),
+                c.z_thrx_alrt_enable(),
+                c.y_thrx_alrt_enable(),
+                c.x_thrx_alrt_enable()
+            )
+        }
+        Register::X_THRX_CONFIG | Register::Y_THRX_CONFIG | Register::Z_THRX_CONFIG => {
+            let [upper, lower] = value.to_be_bytes();
+            write!(s, "[upper={} lower={}]", upper as i8, lower as i8)
+        }
+        Register::T_THRX_CONFIG => {
+            let [upper, lower] = value.to_be_bytes();
+            write!(s, "[upper={} lower={}]", upper, lower)
+        }
+        Register::CONV_STATUS => {
+            let c = ConvStatus::from_u16(value);
+            write!(
+                s,
+                "[rdy={} a={} t={} z={} y={} x={} set_count={} alrt_status={}]",
+                c.rdy(),
+                c.a(),
+                c.t(),
+                c.z(),
+                c.y(),
+                c.x(),
+                c.set_count(),
+                c.alrt_status()
+            )
+        }
+        Register::X_CH_RESULT | Register::Y_CH_RESULT | Register::Z_CH_RESULT => {
+            write!(s, "[{}]", value as i16)
+        }
+        Register::TEMP_RESULT => write!(s, "[{}]", value),
+        Register::AFE_STATUS => {
+            let c = AfeStatus::from_u16(value);
+            write!(
+                s,
+                "[cfg_reset={} sens_stat={} temp_stat={} zhs_stat={} yhs_stat={} xhs_stat={} trim_stat={} ldo_stat={}]",
+                c.cfg_reset(),
+                c.sens_stat(),
+                c.temp_stat(),
+                c.zhs_stat(),
+                c.yhs_stat(),
+                c.xhs_stat(),
+                c.trim_stat(),
+                c.ldo_stat()
+            )
+        }
+        Register::SYS_STATUS => {
+            let c = SysStatus::from_u16(value);
+            write!(
+                s,
+                "[alrt_lvl={} alrt_drv={} sdo_drv={} crc_stat={} frame_stat={} operating_stat={} vcc_ov={} vcc_uv={} temp_thx={} zch_thx={} ych_thx={} xch_thx={}]",
+                c.alrt_lvl(),
+                c.alrt_drv(),
+                c.sdo_drv(),
+                c.crc_stat(),
+                c.frame_stat(),
+                c.operating_stat(),
+                c.vcc_ov(),
+                c.vcc_uv(),
+                c.temp_thx(),
+                c.zch_thx(),
+                c.ych_thx(),
+                c.xch_thx()
+            )
+        }
+        Register::TEST_CONFIG => {
+            let c = TestConfig::form_u16(value);
+            write!(
+                s,
+                "[ver={} crc_dis={} osc_cnt_ctl={:?}]",
+                opt(c.ver()),
+                c.crc_dis(),
+                c.osc_cnt_ctl()
+            )
+        }
+        Register::ANGLE_RESULT => write!(s, "[{} deg]", angle_check::angle_degrees(value)),
+        Register::MAGNITUDE_RESULT => write!(s, "[{}]", angle_check::magnitude_code(value)),
+        Register::OSC_MONITOR | Register::MAG_GAIN_CONFIG => Ok(()),
+    };
+    s
+}
@@ -0,0 +1,211 @@
+//! `defmt::Format` for the register types, printed field by field instead of as raw u16
+
+use defmt::{write, Debug2Format, Format, Formatter};
+
+use crate::alert_config::AlertConfig;
+use crate::device_config::DeviceConfig;
+use crate::frame::FrameStatus;
+use crate::sensor_config::SensorConfig;
+use crate::status::{AfeStatus, ConvStatus, SysStatus};
+use crate::system_config::SystemConfig;
+use crate::test_config::TestConfig;
+use crate::ExtError;
+
+/// Field value, `reserved` if the register holds a code without enum variant
+struct Code<T>(Option<T>);
+
+impl<T: Format> Format for Code<T> {
+    fn format(&self, f: Formatter) {
+        match &self.0 {
+            Some(value) => write!(f, "{}", value),
+            None => write!(f, "reserved"),
+        }
+    }
+}
+
+impl Format for DeviceConfig {
+    fn format(&self, f: Formatter) {
+        write!(
+            f,
+            "DeviceConfig {{ conv_avg: {}, mag_tempco: {}, operating_mode: {}, t_en: {}, t_rate: {}, t_limit_check_en: {}, t_comp_en: {} }}",
+            Code(self.conv_avg()),
+            Code(self.mag_tempco()),
+            Code(self.operating_mode()),
+            self.t_en(),
+            self.t_rate(),
+            self.t_limit_check_en(),
+            self.t_comp_en()
+        )
+    }
+}
+
+impl Format for SensorConfig {
+    fn format(&self, f: Formatter) {
+        write!(
+            f,
+            "SensorConfig {{ angle_en: {}, sleep_time: {}, mag_ch_en: {}, z_range: {}, y_range: {}, x_range: {} }}",
+            self.angle_en(),
+            Code(self.sleep_time()),
+            self.mag_ch_en(),
+            Code(self.z_range()),
+            Code(self.y_range()),
+            Code(self.x_range())
+        )
+    }
+}
+
+impl Format for SystemConfig {
+    fn format(&self, f: Formatter) {
+        write!(
+            f,
+            "SystemConfig {{ diag_sel: {}, trigger_mode: {}, data_type: {}, diag_en: {}, t_z_limit_check: {}, t_y_limit_check: {}, t_x_limit_check: {} }}",
+            self.diag_sel(),
+            Code(self.trigger_mode()),
+            self.data_type(),
+            self.diag_en(),
+            self.t_z_limit_check(),
+            self.t_y_limit_check(),
+            self.t_x_limit_check()
+        )
+    }
+}
+
+impl Format for AlertConfig {
+    fn format(&self, f: Formatter) {
+        write!(
+            f,
+            "AlertConfig {{ alert_latch: {}, alert_mode: {}, status_alrt_enable: {}, rslt_alrt_enable: {}, thrx_count: {}, t_thrx_alrt_enable: {}, z_thrx_alrt_enable: {}, y_thrx_alrt_enable: {}, x_thrx_alrt_enable: {} }}",
+            self.alert_latch(),
+            self.alert_mode(),
+            self.status_alrt_enable(),
+            self.rslt_alrt_enable(),
+            self.thrx_count(),
+            self.t_thrx_alrt_enable(),
+            self.z_thrx_alrt_enable(),
+            self.y_thrx_alrt_enable(),
+            self.x_thrx_alrt_enable()
+        )
+    }
+}
+
+impl Format for TestConfig {
+    fn format(&self, f: Formatter) {
+        write!(
+            f,
+            "TestConfig {{ ver: {}, crc_dis: {}, osc_cnt_ctl: {} }}",
+            Code(self.ver()),
+            self.crc_dis(),
+            self.osc_cnt_ctl()
+        )
+    }
+}
+
+impl Format for ConvStatus {
+    fn format(&self, f: Formatter) {
+        write!(
+            f,
+            "ConvStatus {{ rdy: {}, a: {}, t: {}, z: {}, y: {}, x: {}, set_count: {}, alrt_status: {} }}",
+            self.rdy(),
+            self.a(),
+            self.t(),
+            self.z(),
+            self.y(),
+            self.x(),
+            self.set_count(),
+            self.alrt_status()
+        )
+    }
+}
+
+impl Format for AfeStatus {
+    fn format(&self, f: Formatter) {
+        write!(
+            f,
+            "AfeStatus {{ cfg_reset: {}, sens_stat: {}, temp_stat: {}, zhs_stat: {}, yhs_stat: {}, xhs_stat: {}, trim_stat: {}, ldo_stat: {} }}",
+            self.cfg_reset(),
+            self.sens_stat(),
+            self.temp_stat(),
+            self.zhs_stat(),
+            self.yhs_stat(),
+            self.xhs_stat(),
+            self.trim_stat(),
+            self.ldo_stat()
+        )
+    }
+}
+
+impl Format for SysStatus {
+    fn format(&self, f: Formatter) {
+        write!(
+            f,
+            "SysStatus {{ alrt_lvl: {}, alrt_drv: {}, sdo_drv: {}, crc_stat: {}, frame_stat: {}, operating_stat: {}, vcc_ov: {}, vcc_uv: {}, temp_thx: {}, zch_thx: {}, ych_thx: {}, xch_thx: {} }}",
+            self.alrt_lvl(),
+            self.alrt_drv(),
+            self.sdo_drv(),
+            self.crc_stat(),
+            self.frame_stat(),
+            self.operating_stat(),
+            self.vcc_ov(),
+            self.vcc_uv(),
+            self.temp_thx(),
+            self.zch_thx(),
+            self.ych_thx(),
+            self.xch_thx()
+        )
+    }
+}
+
+impl Format for FrameStatus {
+    fn format(&self, f: Formatter) {
+        write!(
+            f,
+            "FrameStatus {{ prev_crc_error: {}, cfg_reset: {}, sys_alert: {}, afe_alert: {}, x_stat: {}, y_stat: {}, z_stat: {}, t_stat: {} }}",
+            self.prev_crc_error(),
+            self.cfg_reset(),
+            self.sys_alert(),
+            self.afe_alert(),
+            self.x_stat(),
+            self.y_stat(),
+            self.z_stat(),
+            self.t_stat()
+        )
+    }
+}
+
+/// Bus errors of HAL crates rarely implement `Format`, they are printed through `Debug`
+impl<E, PE> Format for ExtError<E, PE>
+where
+    E: core::fmt::Debug,
+    PE: core::fmt::Debug,
+{
+    fn format(&self, f: Formatter) {
+        match self {
+            ExtError::Spi(e) => write!(f, "SPI error: {}", Debug2Format(e)),
+            ExtError::Pin(e) => write!(f, "chip select pin error: {}", Debug2Format(e)),
+            ExtError::Crc => write!(f, "CRC mismatch in SDO frame"),
+            ExtError::Frame(status) => write!(f, "device rejected previous frame ({})", status),
+            ExtError::Timeout => write!(f, "operation timed out"),
+            ExtError::InvalidConfig(issue) => write!(f, "invalid configuration: {}", issue),
+            ExtError::AngleDisabled => write!(f, "angle calculation is disabled"),
+            ExtError::SpecialDataType => write!(f, "register reads need DATA_TYPE Default"),
+            ExtError::NotPresent => write!(f, "device not present"),
+            ExtError::WrongDevice { register, value } => {
+                write!(
+                    f,
+                    "unexpected device, {} reads {=u16:#06x}",
+                    register, value
+                )
+            }
+            ExtError::Mismatch {
+                register,
+                expected,
+                actual,
+                bits,
+            } => write!(
+                f,
+                "{} read back {=u16:#06x} instead of {=u16:#06x} (bits {=u16:#06x})",
+                register, actual, expected, bits
+            ),
+        }
+    }
+}
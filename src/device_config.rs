@@ -1,5 +1,8 @@
 /// Enables additional sampling of the sensor data to reduce the noise
 /// effect (or to increase resolution)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum ConvAvg {
     /// 1x - 13.33Kbps (3-axes) or 40Kpbs (1 axis)
     Avg1x = 0x00,
@@ -20,7 +23,24 @@ pub enum ConvAvg {
     Avg32x = 0x05,
 }
 
+impl ConvAvg {
+    pub(crate) fn from_bits(bits: u16) -> Option<Self> {
+        match bits {
+            0x00 => Some(ConvAvg::Avg1x),
+            0x01 => Some(ConvAvg::Avg2x),
+            0x02 => Some(ConvAvg::Avg4x),
+            0x03 => Some(ConvAvg::Avg8x),
+            0x04 => Some(ConvAvg::Avg16x),
+            0x05 => Some(ConvAvg::Avg32x),
+            _ => None,
+        }
+    }
+}
+
 /// Temperature Coefficient of Sense Magnet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum MagTempco {
     /// 0% (Current sensor applications)
     CurrentSensor = 0x00,
@@ -32,7 +52,21 @@ pub enum MagTempco {
     Ceramic = 0x03,
 }
 
+impl MagTempco {
+    pub(crate) fn from_bits(bits: u16) -> Option<Self> {
+        match bits {
+            0x00 => Some(MagTempco::CurrentSensor),
+            0x01 => Some(MagTempco::NdBFe),
+            0x03 => Some(MagTempco::Ceramic),
+            _ => None,
+        }
+    }
+}
+
 /// Selects Operating Mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum OperatingMode {
     /// Configuration mode, DEFAULT (TRIGGER_MODE Active)
     Configuration = 0x00,
@@ -56,7 +90,25 @@ pub enum OperatingMode {
     DeepSleep = 0x06,
 }
 
+impl OperatingMode {
+    pub(crate) fn from_bits(bits: u16) -> Option<Self> {
+        match bits {
+            0x00 => Some(OperatingMode::Configuration),
+            0x01 => Some(OperatingMode::Standby),
+            0x02 => Some(OperatingMode::Active),
+            0x03 => Some(OperatingMode::ActiveTrigger),
+            0x04 => Some(OperatingMode::WakeupAndSleep),
+            0x05 => Some(OperatingMode::Sleep),
+            0x06 => Some(OperatingMode::DeepSleep),
+            _ => None,
+        }
+    }
+}
+
 /// Temperature Conversion Rate. It is linked to the CONV_AVG field
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum TRate {
     /// Same as other sensors per CONV_AVG, DEFAULT
     SameRate = 0x00,
@@ -65,12 +117,25 @@ pub enum TRate {
     OncePerConvSet = 0x01,
 }
 
+impl TRate {
+    pub(crate) fn from_bits(bits: u16) -> Self {
+        match bits {
+            0x00 => TRate::SameRate,
+            _ => TRate::OncePerConvSet,
+        }
+    }
+}
+
 /// Configure Device Operation Modes - DEVICE_CONFIG
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct DeviceConfig {
     config: u16,
 }
 
 impl DeviceConfig {
+    /// Reserved bits of DEVICE_CONFIG
+    pub const RESERVED_BITS: u16 = 0x8c80;
+
     /// Creates default config
     pub fn new() -> Self {
         let config = 0x00;
@@ -128,6 +193,41 @@ impl DeviceConfig {
         self.config = self.config & !(0b1) | (comp_en as u16);
         self
     }
+
+    /// Get ConvAvg field, `None` if it holds a reserved code
+    pub fn conv_avg(&self) -> Option<ConvAvg> {
+        ConvAvg::from_bits((self.config >> 12) & 0b111)
+    }
+
+    /// Get MagTempco field, `None` if it holds a reserved code
+    pub fn mag_tempco(&self) -> Option<MagTempco> {
+        MagTempco::from_bits((self.config >> 8) & 0b11)
+    }
+
+    /// Get OperatingMode field, `None` if it holds a reserved code
+    pub fn operating_mode(&self) -> Option<OperatingMode> {
+        OperatingMode::from_bits((self.config >> 4) & 0b111)
+    }
+
+    /// Is data acquisition of the temperature channel enabled
+    pub fn t_en(&self) -> bool {
+        self.config & (0b1 << 3) != 0
+    }
+
+    /// Get TRate field
+    pub fn t_rate(&self) -> TRate {
+        TRate::from_bits((self.config >> 2) & 0b1)
+    }
+
+    /// Is temperature limit check enabled
+    pub fn t_limit_check_en(&self) -> bool {
+        self.config & (0b1 << 1) != 0
+    }
+
+    /// Is temperature compensation enabled
+    pub fn t_comp_en(&self) -> bool {
+        self.config & 0b1 != 0
+    }
 }
 
 impl Default for DeviceConfig {
@@ -0,0 +1,215 @@
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::retry::Backoff;
+use crate::status::{AfeStatus, SysStatus};
+use crate::system_config::{DataType, DiagSel, SystemConfig};
+use crate::{ExtError, Register, Tmag5170};
+
+/// Interval between CONV_STATUS polls while waiting for a diagnostic run
+const POLL_INTERVAL_US: u32 = 100;
+
+/// Safety mechanism checked by a diagnostic run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Mechanism {
+    /// Hall sensor bias and offset (AFE_STATUS.SENS_STAT)
+    Sensor,
+
+    /// Temperature sensor (AFE_STATUS.TEMP_STAT)
+    Temperature,
+
+    /// X axis Hall element (AFE_STATUS.XHS_STAT)
+    XHall,
+
+    /// Y axis Hall element (AFE_STATUS.YHS_STAT)
+    YHall,
+
+    /// Z axis Hall element (AFE_STATUS.ZHS_STAT)
+    ZHall,
+
+    /// Trim data (AFE_STATUS.TRIM_STAT)
+    Trim,
+
+    /// LDO (AFE_STATUS.LDO_STAT)
+    Ldo,
+
+    /// ALERT output drive (SYS_STATUS.ALRT_DRV)
+    AlertDrive,
+
+    /// SDO output drive (SYS_STATUS.SDO_DRV)
+    SdoDrive,
+
+    /// SDI frame CRC (SYS_STATUS.CRC_STAT)
+    Crc,
+
+    /// SDI frame length (SYS_STATUS.FRAME_STAT)
+    Frame,
+
+    /// VCC overvoltage (SYS_STATUS.VCC_OV)
+    VccOvervoltage,
+
+    /// VCC undervoltage (SYS_STATUS.VCC_UV)
+    VccUndervoltage,
+}
+
+impl Mechanism {
+    /// All mechanisms reported by [`DiagnosticReport`]
+    pub const ALL: [Mechanism; 13] = [
+        Mechanism::Sensor,
+        Mechanism::Temperature,
+        Mechanism::XHall,
+        Mechanism::YHall,
+        Mechanism::ZHall,
+        Mechanism::Trim,
+        Mechanism::Ldo,
+        Mechanism::AlertDrive,
+        Mechanism::SdoDrive,
+        Mechanism::Crc,
+        Mechanism::Frame,
+        Mechanism::VccOvervoltage,
+        Mechanism::VccUndervoltage,
+    ];
+
+    fn failed(self, afe: AfeStatus, sys: SysStatus) -> bool {
+        match self {
+            Mechanism::Sensor => afe.sens_stat(),
+            Mechanism::Temperature => afe.temp_stat(),
+            Mechanism::XHall => afe.xhs_stat(),
+            Mechanism::YHall => afe.yhs_stat(),
+            Mechanism::ZHall => afe.zhs_stat(),
+            Mechanism::Trim => afe.trim_stat(),
+            Mechanism::Ldo => afe.ldo_stat(),
+            Mechanism::AlertDrive => sys.alrt_drv(),
+            Mechanism::SdoDrive => sys.sdo_drv(),
+            Mechanism::Crc => sys.crc_stat(),
+            Mechanism::Frame => sys.frame_stat(),
+            Mechanism::VccOvervoltage => sys.vcc_ov(),
+            Mechanism::VccUndervoltage => sys.vcc_uv(),
+        }
+    }
+}
+
+/// Outcome of a diagnostic run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DiagnosticReport {
+    diag_sel: DiagSel,
+    afe: AfeStatus,
+    sys: SysStatus,
+}
+
+impl DiagnosticReport {
+    /// Creates report from the status registers read after a diagnostic run
+    pub fn new(diag_sel: DiagSel, afe: AfeStatus, sys: SysStatus) -> Self {
+        DiagnosticReport { diag_sel, afe, sys }
+    }
+
+    /// Diagnostic mode which was run
+    pub fn diag_sel(&self) -> DiagSel {
+        self.diag_sel
+    }
+
+    /// AFE_STATUS read after the run
+    pub fn afe_status(&self) -> AfeStatus {
+        self.afe
+    }
+
+    /// SYS_STATUS read after the run
+    pub fn sys_status(&self) -> SysStatus {
+        self.sys
+    }
+
+    /// Returns true if the mechanism reported an error
+    pub fn is_failed(&self, mechanism: Mechanism) -> bool {
+        mechanism.failed(self.afe, self.sys)
+    }
+
+    /// Returns true if no mechanism reported an error
+    pub fn is_ok(&self) -> bool {
+        self.failed().next().is_none()
+    }
+
+    /// Mechanisms which passed
+    pub fn passed(&self) -> impl Iterator<Item = Mechanism> + '_ {
+        Mechanism::ALL
+            .iter()
+            .copied()
+            .filter(move |&m| !self.is_failed(m))
+    }
+
+    /// Mechanisms which reported an error
+    pub fn failed(&self) -> impl Iterator<Item = Mechanism> + '_ {
+        Mechanism::ALL
+            .iter()
+            .copied()
+            .filter(move |&m| self.is_failed(m))
+    }
+}
+
+impl<SPI, CS, B, E, PE> Tmag5170<SPI, CS, B>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    B: Backoff,
+{
+    /// Runs a DIAG_SEL diagnostic cycle with regular frames and restores the previous
+    /// SYSTEM_CONFIG. CONV_STATUS is polled until SET_COUNT changes or `timeout_us` elapses
+    pub fn run_diagnostics<D: DelayUs<u32>>(
+        &mut self,
+        diag_sel: DiagSel,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<DiagnosticReport, ExtError<E, PE>> {
+        // Special frames carry no register value, the last written config is used then
+        let previous = match self.data_type {
+            DataType::Default => {
+                SystemConfig::form_u16(self.read_register(Register::SYSTEM_CONFIG, 0x00)?)
+            }
+            _ => self.config().system,
+        };
+
+        // Status registers are cleared on read, drop stale flags
+        self.read_afe_status()?;
+        self.read_sys_status()?;
+
+        let diagnostic = previous
+            .set_data_type(DataType::Default)
+            .set_diag_sel(diag_sel)
+            .set_diag_en(true);
+        self.apply_system_config(diagnostic)?;
+        let res = self.diagnostic_cycle(delay, timeout_us);
+        self.apply_system_config(previous)?;
+
+        let (afe, sys) = res?;
+        Ok(DiagnosticReport::new(diag_sel, afe, sys))
+    }
+
+    fn diagnostic_cycle<D: DelayUs<u32>>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<(AfeStatus, SysStatus), ExtError<E, PE>> {
+        // RDY may be left over from an earlier conversion
+        let set_count = self.read_conv_status()?.set_count();
+        self.conv_start()?;
+
+        let mut waited_us: u32 = 0;
+        loop {
+            let conv = self.read_conv_status()?;
+            if conv.rdy() && conv.set_count() != set_count {
+                break;
+            }
+            if waited_us >= timeout_us {
+                return Err(ExtError::Timeout);
+            }
+            delay.delay_us(POLL_INTERVAL_US);
+            waited_us = waited_us.saturating_add(POLL_INTERVAL_US);
+        }
+
+        let afe = self.read_afe_status()?;
+        let sys = self.read_sys_status()?;
+        Ok((afe, sys))
+    }
+}
@@ -0,0 +1,275 @@
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::alert_config::AlertConfig;
+use crate::device_config::DeviceConfig;
+use crate::retry::Backoff;
+use crate::sensor_config::SensorConfig;
+use crate::status::{AfeStatus, ConvStatus, SysStatus};
+use crate::system_config::{DataType, SystemConfig};
+use crate::test_config::TestConfig;
+use crate::{ExtError, Register, Tmag5170};
+
+/// Writable registers in restore order, DEVICE_CONFIG last so the operating mode
+/// changes once everything else is set. TEST_CONFIG only holds CRC_DIS and OSC_CNT_CTL
+/// and is restored on request
+const RESTORE_ORDER: [Register; 9] = [
+    Register::SENSOR_CONFIG,
+    Register::SYSTEM_CONFIG,
+    Register::ALERT_CONFIG,
+    Register::X_THRX_CONFIG,
+    Register::Y_THRX_CONFIG,
+    Register::Z_THRX_CONFIG,
+    Register::T_THRX_CONFIG,
+    Register::MAG_GAIN_CONFIG,
+    Register::DEVICE_CONFIG,
+];
+
+/// Snapshot of every register in [`Register::ALL`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RegisterDump {
+    values: [u16; Register::ALL.len()],
+}
+
+impl Default for RegisterDump {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegisterDump {
+    /// Creates dump with all registers zero
+    pub fn new() -> Self {
+        RegisterDump {
+            values: [0; Register::ALL.len()],
+        }
+    }
+
+    /// Register value
+    pub fn get(&self, reg: Register) -> u16 {
+        self.values[index(reg)]
+    }
+
+    /// Sets a register value
+    pub fn set(mut self, reg: Register, value: u16) -> Self {
+        self.values[index(reg)] = value;
+        self
+    }
+
+    /// Registers with their values in address order
+    pub fn iter(&self) -> impl Iterator<Item = (Register, u16)> + '_ {
+        Register::ALL
+            .iter()
+            .copied()
+            .zip(self.values.iter().copied())
+    }
+
+    /// Registers whose writable bits differ. Returns (register, self, other)
+    pub fn diff<'a>(
+        &'a self,
+        other: &'a RegisterDump,
+    ) -> impl Iterator<Item = (Register, u16, u16)> + 'a {
+        self.iter()
+            .zip(other.iter())
+            .filter(|((reg, a), (_, b))| (a ^ b) & reg.writable_bits() != 0)
+            .map(|((reg, a), (_, b))| (reg, a, b))
+    }
+
+    /// DEVICE_CONFIG
+    pub fn device_config(&self) -> DeviceConfig {
+        DeviceConfig::form_u16(self.get(Register::DEVICE_CONFIG))
+    }
+
+    /// SENSOR_CONFIG
+    pub fn sensor_config(&self) -> SensorConfig {
+        SensorConfig::form_u16(self.get(Register::SENSOR_CONFIG))
+    }
+
+    /// SYSTEM_CONFIG
+    pub fn system_config(&self) -> SystemConfig {
+        SystemConfig::form_u16(self.get(Register::SYSTEM_CONFIG))
+    }
+
+    /// ALERT_CONFIG
+    pub fn alert_config(&self) -> AlertConfig {
+        AlertConfig::form_u16(self.get(Register::ALERT_CONFIG))
+    }
+
+    /// TEST_CONFIG
+    pub fn test_config(&self) -> TestConfig {
+        TestConfig::form_u16(self.get(Register::TEST_CONFIG))
+    }
+
+    /// CONV_STATUS
+    pub fn conv_status(&self) -> ConvStatus {
+        ConvStatus::from_u16(self.get(Register::CONV_STATUS))
+    }
+
+    /// AFE_STATUS
+    pub fn afe_status(&self) -> AfeStatus {
+        AfeStatus::from_u16(self.get(Register::AFE_STATUS))
+    }
+
+    /// SYS_STATUS
+    pub fn sys_status(&self) -> SysStatus {
+        SysStatus::from_u16(self.get(Register::SYS_STATUS))
+    }
+}
+
+fn index(reg: Register) -> usize {
+    Register::ALL
+        .iter()
+        .position(|&r| r == reg)
+        .unwrap_or_default()
+}
+
+impl<SPI, CS, B, E, PE> Tmag5170<SPI, CS, B>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    B: Backoff,
+{
+    /// Reads every register. AFE_STATUS and SYS_STATUS flags are cleared by the read.
+    /// Returns ExtError::SpecialDataType unless DATA_TYPE is Default, special reads don't
+    /// return register values
+    pub fn dump_registers(&mut self) -> Result<RegisterDump, ExtError<E, PE>> {
+        if self.data_type != DataType::Default {
+            return Err(ExtError::SpecialDataType);
+        }
+
+        let mut dump = RegisterDump::new();
+        for &reg in Register::ALL.iter() {
+            dump = dump.set(reg, self.read_register(reg, 0x00)?);
+        }
+
+        Ok(dump)
+    }
+
+    /// Writes the writable bits of a dump back, reserved and read only bits are
+    /// written as zero. DEVICE_CONFIG is written last. TEST_CONFIG is left alone,
+    /// see [`Tmag5170::restore_test_config`]
+    pub fn restore_registers(&mut self, dump: &RegisterDump) -> Result<(), ExtError<E, PE>> {
+        for &reg in RESTORE_ORDER.iter() {
+            let value = dump.get(reg) & reg.writable_bits();
+            match reg {
+                Register::DEVICE_CONFIG => {
+                    self.apply_device_config(DeviceConfig::form_u16(value))?
+                }
+                Register::SENSOR_CONFIG => {
+                    self.apply_sensor_config(SensorConfig::form_u16(value))?
+                }
+                Register::SYSTEM_CONFIG => {
+                    self.apply_system_config(SystemConfig::form_u16(value))?
+                }
+                Register::ALERT_CONFIG => self.apply_alert_config(AlertConfig::form_u16(value))?,
+                _ => self.write_register(reg, value, 0x00)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes CRC_DIS and OSC_CNT_CTL of a dump back. A set CRC_DIS turns off the
+    /// CRC check of the SDI frames
+    pub fn restore_test_config(&mut self, dump: &RegisterDump) -> Result<(), ExtError<E, PE>> {
+        let reg = Register::TEST_CONFIG;
+        self.write_register(reg, dump.get(reg) & reg.writable_bits(), 0x00)
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::text::ParseError;
+
+#[cfg(feature = "std")]
+mod text {
+    use core::fmt;
+    use std::format;
+    use std::string::String;
+
+    use super::RegisterDump;
+    use crate::decode;
+    use crate::Register;
+
+    /// Errors of the text dump parser
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum ParseError {
+        /// Line (1-based) is not `NAME = VALUE` with a known register and a 16-bit value
+        Line(usize),
+        /// Register is listed twice
+        Duplicate(Register),
+        /// Register is not listed
+        Missing(Register),
+    }
+
+    impl fmt::Display for ParseError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ParseError::Line(line) => write!(f, "invalid line {}", line),
+                ParseError::Duplicate(reg) => write!(f, "{:?} is listed twice", reg),
+                ParseError::Missing(reg) => write!(f, "{:?} is missing", reg),
+            }
+        }
+    }
+
+    impl std::error::Error for ParseError {}
+
+    impl RegisterDump {
+        /// Writes one `NAME = 0xVALUE` line per register, decoded fields follow as comment
+        pub fn to_text(&self) -> String {
+            let mut text = String::from("# TMAG5170-Q1 register dump\n");
+            for (reg, value) in self.iter() {
+                let line = format!(
+                    "{:<16} = {:#06x}  # {}",
+                    format!("{:?}", reg),
+                    value,
+                    decode::fields(reg, value)
+                );
+                text += line.trim_end_matches(&[' ', '#'][..]);
+                text.push('\n');
+            }
+            text
+        }
+
+        /// Parses the format of [`RegisterDump::to_text`]. Names are case insensitive,
+        /// values hexadecimal with `0x` prefix or decimal, `#` starts a comment
+        pub fn from_text(text: &str) -> Result<Self, ParseError> {
+            let mut dump = RegisterDump::new();
+            let mut seen = [false; Register::ALL.len()];
+            for (idx, line) in text.lines().enumerate() {
+                let line = line.split('#').next().unwrap_or("").trim();
+                if line.is_empty() {
+                    continue;
+                }
+                let (reg, value) = parse_line(line).ok_or(ParseError::Line(idx + 1))?;
+                let i = super::index(reg);
+                if seen[i] {
+                    return Err(ParseError::Duplicate(reg));
+                }
+                seen[i] = true;
+                dump = dump.set(reg, value);
+            }
+            match Register::ALL.iter().zip(seen.iter()).find(|(_, &s)| !s) {
+                Some((&reg, _)) => Err(ParseError::Missing(reg)),
+                None => Ok(dump),
+            }
+        }
+    }
+
+    fn parse_line(line: &str) -> Option<(Register, u16)> {
+        let (name, value) = line.split_once('=')?;
+        let (name, value) = (name.trim(), value.trim());
+        let reg = Register::ALL
+            .iter()
+            .copied()
+            .find(|reg| format!("{:?}", reg).eq_ignore_ascii_case(name))?;
+        let value = match value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+        {
+            Some(hex) => u16::from_str_radix(hex, 16).ok()?,
+            None => value.parse().ok()?,
+        };
+        Some((reg, value))
+    }
+}
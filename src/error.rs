@@ -0,0 +1,119 @@
+use core::fmt;
+
+use crate::frame::FrameStatus;
+use crate::validation::ConfigIssue;
+use crate::Register;
+
+/// TMAG5170-Q1 error type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExtError<E, PE> {
+    /// Generic SPI error
+    Spi(E),
+
+    /// Chip select pin error
+    Pin(PE),
+
+    /// CRC of the SDO frame doesn't match its content
+    Crc,
+
+    /// Device reports that the SDI frame of a write or command was corrupted and ignored
+    Frame(FrameStatus),
+
+    /// Device didn't complete the operation in time
+    Timeout,
+
+    /// Configuration rejected by validation
+    InvalidConfig(ConfigIssue),
+
+    /// Operation needs the angle calculation while AngleEn is NoAngle
+    AngleDisabled,
+
+    /// Operation reads registers while DATA_TYPE selects special frames without register values
+    SpecialDataType,
+
+    /// No device answers on the bus, the SDO frame is all zeros or all ones
+    NotPresent,
+
+    /// Register doesn't hold the TMAG5170-Q1 signature
+    WrongDevice {
+        /// Checked register
+        register: Register,
+
+        /// Read value
+        value: u16,
+    },
+
+    /// Register read back after a write differs from the written value
+    Mismatch {
+        /// Written register
+        register: Register,
+
+        /// Written value, writable bits only
+        expected: u16,
+
+        /// Read back value, writable bits only
+        actual: u16,
+
+        /// Bits which differ
+        bits: u16,
+    },
+}
+
+impl<E, PE> fmt::Display for ExtError<E, PE>
+where
+    E: fmt::Debug,
+    PE: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtError::Spi(e) => write!(f, "SPI error: {:?}", e),
+            ExtError::Pin(e) => write!(f, "chip select pin error: {:?}", e),
+            ExtError::Crc => write!(f, "CRC mismatch in SDO frame"),
+            ExtError::Frame(status) => write!(
+                f,
+                "device rejected previous frame (status {:#05x})",
+                status.to_u16()
+            ),
+            ExtError::Timeout => write!(f, "operation timed out"),
+            ExtError::InvalidConfig(issue) => write!(f, "invalid configuration: {:?}", issue),
+            ExtError::AngleDisabled => write!(f, "angle calculation is disabled"),
+            ExtError::SpecialDataType => write!(f, "register reads need DATA_TYPE Default"),
+            ExtError::NotPresent => write!(f, "device not present"),
+            ExtError::WrongDevice { register, value } => {
+                write!(f, "unexpected device, {:?} reads {:#06x}", register, value)
+            }
+            ExtError::Mismatch {
+                register,
+                expected,
+                actual,
+                bits,
+            } => write!(
+                f,
+                "{:?} read back {:#06x} instead of {:#06x} (bits {:#06x})",
+                register, actual, expected, bits
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E, PE> std::error::Error for ExtError<E, PE>
+where
+    E: fmt::Debug,
+    PE: fmt::Debug,
+{
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<E, PE> embedded_hal_1::spi::Error for ExtError<E, PE>
+where
+    E: embedded_hal_1::spi::Error,
+    PE: fmt::Debug,
+{
+    fn kind(&self) -> embedded_hal_1::spi::ErrorKind {
+        match self {
+            ExtError::Spi(e) => e.kind(),
+            _ => embedded_hal_1::spi::ErrorKind::Other,
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crc_all::Crc;
+
+use crate::system_config::DataType;
+use crate::Register;
+
+/// Status bits returned by TMAG5170-Q1 in every SDO frame (STAT[11:0])
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameStatus {
+    status: u16,
+}
+
+impl FrameStatus {
+    /// Creates status from the 12 STAT bits
+    pub fn from_u16(status: u16) -> Self {
+        FrameStatus {
+            status: status & 0x0fff,
+        }
+    }
+
+    /// Convert status to u16 value
+    pub fn to_u16(&self) -> u16 {
+        self.status
+    }
+
+    /// Sets CRC error of the previous SDI frame
+    pub fn set_prev_crc_error(mut self, prev_crc_error: bool) -> Self {
+        self.status = self.status & !(0b1 << 11) | ((prev_crc_error as u16) << 11);
+        self
+    }
+
+    /// Sets device reset flag
+    pub fn set_cfg_reset(mut self, cfg_reset: bool) -> Self {
+        self.status = self.status & !(0b1 << 10) | ((cfg_reset as u16) << 10);
+        self
+    }
+
+    /// Sets SYS_STATUS alert
+    pub fn set_sys_alert(mut self, sys_alert: bool) -> Self {
+        self.status = self.status & !(0b1 << 9) | ((sys_alert as u16) << 9);
+        self
+    }
+
+    /// Sets AFE_STATUS alert
+    pub fn set_afe_alert(mut self, afe_alert: bool) -> Self {
+        self.status = self.status & !(0b1 << 8) | ((afe_alert as u16) << 8);
+        self
+    }
+
+    /// Sets new X channel result
+    pub fn set_x_stat(mut self, x_stat: bool) -> Self {
+        self.status = self.status & !(0b1 << 7) | ((x_stat as u16) << 7);
+        self
+    }
+
+    /// Sets new Y channel result
+    pub fn set_y_stat(mut self, y_stat: bool) -> Self {
+        self.status = self.status & !(0b1 << 6) | ((y_stat as u16) << 6);
+        self
+    }
+
+    /// Sets new Z channel result
+    pub fn set_z_stat(mut self, z_stat: bool) -> Self {
+        self.status = self.status & !(0b1 << 5) | ((z_stat as u16) << 5);
+        self
+    }
+
+    /// Sets new temperature result
+    pub fn set_t_stat(mut self, t_stat: bool) -> Self {
+        self.status = self.status & !(0b1 << 4) | ((t_stat as u16) << 4);
+        self
+    }
+
+    /// CRC error was detected in the previous SDI frame, the previous command was ignored
+    pub fn prev_crc_error(&self) -> bool {
+        self.status & (0b1 << 11) != 0
+    }
+
+    /// Device has been reset and registers hold their default values
+    pub fn cfg_reset(&self) -> bool {
+        self.status & (0b1 << 10) != 0
+    }
+
+    /// SYS_STATUS register reports an alert
+    pub fn sys_alert(&self) -> bool {
+        self.status & (0b1 << 9) != 0
+    }
+
+    /// AFE_STATUS register reports an alert
+    pub fn afe_alert(&self) -> bool {
+        self.status & (0b1 << 8) != 0
+    }
+
+    /// New X channel result is available
+    pub fn x_stat(&self) -> bool {
+        self.status & (0b1 << 7) != 0
+    }
+
+    /// New Y channel result is available
+    pub fn y_stat(&self) -> bool {
+        self.status & (0b1 << 6) != 0
+    }
+
+    /// New Z channel result is available
+    pub fn z_stat(&self) -> bool {
+        self.status & (0b1 << 5) != 0
+    }
+
+    /// New temperature result is available
+    pub fn t_stat(&self) -> bool {
+        self.status & (0b1 << 4) != 0
+    }
+}
+
+/// Builds a SDI frame and fills in its CRC
+pub(crate) fn request(crc: &mut Crc<u8>, addr: u8, value: u16, cmd: u8) -> [u8; 4] {
+    let value_bytes = value.to_be_bytes();
+    let mut buffer = [addr, value_bytes[0], value_bytes[1], cmd << 4];
+    buffer[3] |= crc4(crc, &buffer);
+    buffer
+}
+
+/// Frame of a floating MISO line or an unpowered device, all bits low or all bits high
+pub(crate) fn is_blank(buffer: &[u8; 4]) -> bool {
+    buffer.iter().all(|&b| b == 0x00) || buffer.iter().all(|&b| b == 0xff)
+}
+
+/// Checks the CRC of a SDO frame
+pub(crate) fn crc_ok(crc: &mut Crc<u8>, buffer: &[u8; 4]) -> bool {
+    let packet_crc4 = buffer[3] & 0x0f; // save crc
+    let mut buffer = *buffer;
+    buffer[3] &= 0xf0; // erase crc in buffer
+    packet_crc4 == crc4(crc, &buffer)
+}
+
+/// Status of a regular 32-bit SDO frame
+pub(crate) fn status(buffer: &[u8; 4]) -> FrameStatus {
+    FrameStatus::from_u16(((buffer[0] as u16) << 4) + (buffer[3] >> 4) as u16)
+}
+
+/// Status of a special 12-bit data SDO frame, only STAT[11:8] are sent
+pub(crate) fn special_status(buffer: &[u8; 4]) -> FrameStatus {
+    FrameStatus::from_u16(((buffer[3] >> 4) as u16) << 8)
+}
+
+/// Register value of a regular 32-bit SDO frame
+pub(crate) fn register_value(buffer: &[u8; 4]) -> u16 {
+    ((buffer[1] as u16) << 8) + buffer[2] as u16
+}
+
+/// Two 12-bit channels of a special SDO frame. Returns (ch1,ch2)
+pub(crate) fn special_values(buffer: &[u8; 4]) -> (u16, u16) {
+    let ch1 = ((buffer[1] as u16) << 4) + (buffer[2] & 0x0f) as u16;
+    let ch2 = ((buffer[0] as u16) << 4) + (buffer[2] >> 4) as u16;
+    (ch1, ch2)
+}
+
+/// Status of a SDO frame in the format selected by DATA_TYPE
+pub(crate) fn parse_status(buffer: &[u8; 4], data_type: DataType) -> FrameStatus {
+    match data_type {
+        DataType::Default => status(buffer),
+        _ => special_status(buffer),
+    }
+}
+
+/// PREV_CRC_STAT of a SDO frame in the format selected by DATA_TYPE
+pub(crate) fn prev_crc_error(buffer: &[u8; 4], data_type: DataType) -> bool {
+    parse_status(buffer, data_type).prev_crc_error()
+}
+
+/// Checks the SDO frame of a read of `reg` without command for PREV_CRC_STAT, i.e. whether
+/// the frame before was executed. `value` is the value `reg` holds if it was.
+///
+/// A SYSTEM_CONFIG write which switches between regular and special frames changes the
+/// format of the frame, the PREV_CRC_STAT position of the old format then holds data or
+/// STAT[3]. `None` if both formats are consistent with the frame, PREV_CRC_STAT of the
+/// old format in a second read tells them apart: it is cleared if the write was ignored
+pub(crate) fn executed(
+    buffer: &[u8; 4],
+    reg: Register,
+    value: u16,
+    before: DataType,
+    after: DataType,
+) -> Option<bool> {
+    let regular_frames = |data_type| data_type == DataType::Default;
+    if regular_frames(before) == regular_frames(after) {
+        return Some(!prev_crc_error(buffer, after));
+    }
+    if !prev_crc_error(buffer, before) {
+        return Some(true);
+    }
+
+    let consistent = match after {
+        DataType::Default => {
+            !status(buffer).prev_crc_error()
+                && (register_value(buffer) ^ value) & reg.writable_bits() == 0
+        }
+        _ => !special_status(buffer).prev_crc_error(),
+    };
+    if consistent {
+        None
+    } else {
+        Some(false)
+    }
+}
+
+/// Builds a regular 32-bit SDO frame and fills in its CRC
+#[cfg(feature = "std")]
+pub(crate) fn response(crc: &mut Crc<u8>, status: FrameStatus, value: u16) -> [u8; 4] {
+    let status = status.to_u16();
+    let value_bytes = value.to_be_bytes();
+    let mut buffer = [
+        (status >> 4) as u8,
+        value_bytes[0],
+        value_bytes[1],
+        ((status & 0x0f) as u8) << 4,
+    ];
+    buffer[3] |= crc4(crc, &buffer);
+    buffer
+}
+
+/// Builds a special 12-bit data SDO frame and fills in its CRC
+#[cfg(feature = "std")]
+pub(crate) fn special_response(
+    crc: &mut Crc<u8>,
+    status: FrameStatus,
+    ch1: u16,
+    ch2: u16,
+) -> [u8; 4] {
+    let mut buffer = [
+        (ch2 >> 4) as u8,
+        (ch1 >> 4) as u8,
+        (((ch2 & 0x0f) as u8) << 4) | (ch1 & 0x0f) as u8,
+        ((status.to_u16() >> 8) as u8) << 4,
+    ];
+    buffer[3] |= crc4(crc, &buffer);
+    buffer
+}
+
+fn crc4(crc: &mut Crc<u8>, buffer: &[u8; 4]) -> u8 {
+    crc.init();
+    crc.update(buffer);
+    crc.finish()
+}
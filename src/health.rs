@@ -0,0 +1,329 @@
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::frame::FrameStatus;
+use crate::retry::Backoff;
+use crate::status::{AfeStatus, ConvStatus, SysStatus};
+use crate::{ExtError, Tmag5170};
+
+/// Number of faults tracked by [`HealthMonitor`]
+pub const FAULT_COUNT: usize = 19;
+
+/// Fault tracked by the health monitor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Fault {
+    /// Hall sensor bias or offset error (AFE_STATUS.SENS_STAT)
+    Sensor = 0,
+
+    /// Temperature sensor error (AFE_STATUS.TEMP_STAT)
+    Temperature = 1,
+
+    /// X axis Hall element error (AFE_STATUS.XHS_STAT)
+    XHall = 2,
+
+    /// Y axis Hall element error (AFE_STATUS.YHS_STAT)
+    YHall = 3,
+
+    /// Z axis Hall element error (AFE_STATUS.ZHS_STAT)
+    ZHall = 4,
+
+    /// Trim data error (AFE_STATUS.TRIM_STAT)
+    Trim = 5,
+
+    /// LDO error (AFE_STATUS.LDO_STAT)
+    Ldo = 6,
+
+    /// Device was reset (AFE_STATUS.CFG_RESET)
+    ConfigReset = 7,
+
+    /// ALERT output drive error (SYS_STATUS.ALRT_DRV)
+    AlertDrive = 8,
+
+    /// SDO output drive error (SYS_STATUS.SDO_DRV)
+    SdoDrive = 9,
+
+    /// VCC overvoltage (SYS_STATUS.VCC_OV)
+    VccOvervoltage = 10,
+
+    /// VCC undervoltage (SYS_STATUS.VCC_UV)
+    VccUndervoltage = 11,
+
+    /// Device detected a CRC error in a SDI frame (SYS_STATUS.CRC_STAT or frame status)
+    SpiCrc = 12,
+
+    /// Device detected a frame with a wrong length (SYS_STATUS.FRAME_STAT)
+    SpiFrame = 13,
+
+    /// Magnetic or temperature limit check failed (SYS_STATUS.*_THX)
+    LimitExceeded = 14,
+
+    /// Status registers couldn't be read
+    Communication = 15,
+
+    /// On-chip angle or magnitude disagrees with the channel results
+    AngleMismatch = 16,
+
+    /// Results or SET_COUNT stopped changing
+    StuckOutput = 17,
+
+    /// Conversion reported an ALERT condition (CONV_STATUS.ALRT_STATUS)
+    ConversionAlert = 18,
+}
+
+impl Fault {
+    /// All faults in bit order
+    pub const ALL: [Fault; FAULT_COUNT] = [
+        Fault::Sensor,
+        Fault::Temperature,
+        Fault::XHall,
+        Fault::YHall,
+        Fault::ZHall,
+        Fault::Trim,
+        Fault::Ldo,
+        Fault::ConfigReset,
+        Fault::AlertDrive,
+        Fault::SdoDrive,
+        Fault::VccOvervoltage,
+        Fault::VccUndervoltage,
+        Fault::SpiCrc,
+        Fault::SpiFrame,
+        Fault::LimitExceeded,
+        Fault::Communication,
+        Fault::AngleMismatch,
+        Fault::StuckOutput,
+        Fault::ConversionAlert,
+    ];
+
+    /// Returns true if the fault makes the measurements untrustworthy
+    pub fn is_critical(self) -> bool {
+        !matches!(
+            self,
+            Fault::SpiCrc
+                | Fault::SpiFrame
+                | Fault::LimitExceeded
+                | Fault::Communication
+                | Fault::ConversionAlert
+        )
+    }
+}
+
+/// Set of faults
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Faults {
+    bits: u32,
+}
+
+impl Faults {
+    /// Creates empty set
+    pub fn new() -> Self {
+        Faults { bits: 0 }
+    }
+
+    /// Adds the fault to the set
+    pub fn insert(&mut self, fault: Fault) {
+        self.bits |= 1 << fault as u32;
+    }
+
+    /// Removes the fault from the set
+    pub fn remove(&mut self, fault: Fault) {
+        self.bits &= !(1 << fault as u32);
+    }
+
+    /// Returns true if the set contains the fault
+    pub fn contains(&self, fault: Fault) -> bool {
+        self.bits & (1 << fault as u32) != 0
+    }
+
+    /// Returns true if the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Iterates over the faults of the set
+    pub fn iter(&self) -> impl Iterator<Item = Fault> + '_ {
+        Fault::ALL
+            .iter()
+            .copied()
+            .filter(move |&f| self.contains(f))
+    }
+
+    fn critical(&self) -> Faults {
+        let mut critical = Faults::new();
+        for fault in self.iter().filter(|f| f.is_critical()) {
+            critical.insert(fault);
+        }
+        critical
+    }
+
+    /// Collects the faults reported by the status registers
+    pub fn from_status(
+        afe: AfeStatus,
+        sys: SysStatus,
+        conv: ConvStatus,
+        frame: FrameStatus,
+    ) -> Self {
+        let mut faults = Faults::new();
+        let checks = [
+            (afe.sens_stat(), Fault::Sensor),
+            (afe.temp_stat(), Fault::Temperature),
+            (afe.xhs_stat(), Fault::XHall),
+            (afe.yhs_stat(), Fault::YHall),
+            (afe.zhs_stat(), Fault::ZHall),
+            (afe.trim_stat(), Fault::Trim),
+            (afe.ldo_stat(), Fault::Ldo),
+            (afe.cfg_reset() || frame.cfg_reset(), Fault::ConfigReset),
+            (sys.alrt_drv(), Fault::AlertDrive),
+            (sys.sdo_drv(), Fault::SdoDrive),
+            (sys.vcc_ov(), Fault::VccOvervoltage),
+            (sys.vcc_uv(), Fault::VccUndervoltage),
+            (sys.crc_stat() || frame.prev_crc_error(), Fault::SpiCrc),
+            (sys.frame_stat(), Fault::SpiFrame),
+            (sys.limit_check().any(), Fault::LimitExceeded),
+            (conv.alrt_status() != 0, Fault::ConversionAlert),
+        ];
+        for &(failed, fault) in checks.iter() {
+            if failed {
+                faults.insert(fault);
+            }
+        }
+        faults
+    }
+}
+
+/// Aggregated health of the sensor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HealthState {
+    /// No fault
+    Ok,
+
+    /// Non-critical faults were seen in the latest sample
+    Degraded(Faults),
+
+    /// Critical faults were latched, measurements are untrustworthy
+    Faulted(Faults),
+}
+
+/// Latches faults, counts them and records their first occurrence
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HealthMonitor {
+    started: bool,
+    latched: Faults,
+    current: Faults,
+    counters: [u32; FAULT_COUNT],
+    first_seen: [Option<u64>; FAULT_COUNT],
+}
+
+impl HealthMonitor {
+    /// Creates monitor without faults
+    pub fn new() -> Self {
+        HealthMonitor {
+            started: false,
+            latched: Faults::new(),
+            current: Faults::new(),
+            counters: [0; FAULT_COUNT],
+            first_seen: [None; FAULT_COUNT],
+        }
+    }
+
+    /// Records the faults of one sample taken at `now`, in units of the user clock.
+    /// Fault::ConfigReset in the first sample read from the device is the power-up reset
+    /// and is ignored, samples with Fault::Communication don't count as read
+    pub fn update(&mut self, now: u64, mut faults: Faults) -> HealthState {
+        if !self.started && !faults.contains(Fault::Communication) {
+            self.started = true;
+            faults.remove(Fault::ConfigReset);
+        }
+        for fault in faults.iter() {
+            let idx = fault as usize;
+            self.counters[idx] = self.counters[idx].saturating_add(1);
+            if self.first_seen[idx].is_none() {
+                self.first_seen[idx] = Some(now);
+            }
+            self.latched.insert(fault);
+        }
+        self.current = faults;
+
+        self.state()
+    }
+
+    /// Critical faults are latched until [`HealthMonitor::clear_latched`],
+    /// non-critical faults are taken from the latest sample
+    pub fn state(&self) -> HealthState {
+        let critical = self.latched.critical();
+        if !critical.is_empty() {
+            HealthState::Faulted(critical)
+        } else if !self.current.is_empty() {
+            HealthState::Degraded(self.current)
+        } else {
+            HealthState::Ok
+        }
+    }
+
+    /// Faults seen since the last [`HealthMonitor::clear_latched`]
+    pub fn latched(&self) -> Faults {
+        self.latched
+    }
+
+    /// Number of samples which reported the fault
+    pub fn count(&self, fault: Fault) -> u32 {
+        self.counters[fault as usize]
+    }
+
+    /// Time of the first sample which reported the fault
+    pub fn first_occurrence(&self, fault: Fault) -> Option<u64> {
+        self.first_seen[fault as usize]
+    }
+
+    /// Clears the latched faults, counters and timestamps are kept
+    pub fn clear_latched(&mut self) {
+        self.latched = Faults::new();
+        self.current = Faults::new();
+    }
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        HealthMonitor::new()
+    }
+}
+
+impl<SPI, CS, B, E, PE> Tmag5170<SPI, CS, B>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    B: Backoff,
+{
+    /// Reads AFE_STATUS, SYS_STATUS and CONV_STATUS, and feeds them with the latest frame status
+    /// to the monitor.
+    /// A failed read is recorded as Fault::Communication before the error is returned
+    pub fn check_health(
+        &mut self,
+        monitor: &mut HealthMonitor,
+        now: u64,
+    ) -> Result<HealthState, ExtError<E, PE>> {
+        match self.read_health_status() {
+            Ok((afe, sys, conv)) => {
+                let faults = Faults::from_status(afe, sys, conv, self.frame_status());
+                Ok(monitor.update(now, faults))
+            }
+            Err(e) => {
+                let mut faults = Faults::new();
+                faults.insert(Fault::Communication);
+                monitor.update(now, faults);
+                Err(e)
+            }
+        }
+    }
+
+    fn read_health_status(
+        &mut self,
+    ) -> Result<(AfeStatus, SysStatus, ConvStatus), ExtError<E, PE>> {
+        let afe = self.read_afe_status()?;
+        let sys = self.read_sys_status()?;
+        let conv = self.read_conv_status()?;
+        Ok((afe, sys, conv))
+    }
+}
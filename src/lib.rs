@@ -8,6 +8,9 @@
 #![deny(warnings)]
 #![no_std]
 
+#[cfg(feature = "std")]
+extern crate std;
+
 use embedded_hal::blocking::spi::Transfer;
 use embedded_hal::digital::v2::OutputPin;
 use embedded_hal::spi::Mode;
@@ -26,147 +29,485 @@ pub mod system_config;
 /// Implements ALERT_CONFIG Register
 pub mod alert_config;
 
+/// Implements CONV_STATUS, AFE_STATUS and SYS_STATUS Registers
+pub mod status;
+
+/// Implements DIAG_SEL diagnostic runs
+pub mod diagnostics;
+
+/// Implements TEST_CONFIG Register
+pub mod test_config;
+
+/// Implements device presence and identity check
+pub mod probe;
+
+/// Implements startup self-test
+pub mod self_test;
+
+/// Implements register snapshots and their restoration
+pub mod dump;
+
+/// Implements device reset detection and reconfiguration
+pub mod reset;
+
+/// Implements supply voltage fault monitoring
+pub mod supply;
+
+/// Implements aggregated health monitoring
+pub mod health;
+
+/// Implements magnetic field and temperature limit check reporting
+pub mod limit_check;
+
+/// Implements software cross-check of the on-chip angle calculation
+pub mod angle_check;
+
+/// Implements stuck output and frozen data detection
+pub mod stuck;
+
+/// Implements SPI link quality statistics
+#[cfg(feature = "link-stats")]
+pub mod stats;
+
+#[cfg(feature = "log")]
+mod trace;
+
+/// Implements semantic validation of the configuration registers
+pub mod validation;
+
+/// Implements SPI frame format and SDO status bits
+pub mod frame;
+
+/// Implements automatic retry of failed transactions
+pub mod retry;
+
+/// Implements sharing of one SPI bus between several drivers
+pub mod bus;
+
+/// Implements an array of sensors sharing one SPI bus
+pub mod array;
+
+/// Implements time-varying field scenarios and their conversion to register codes
+#[cfg(feature = "std")]
+pub mod scenario;
+
+/// Implements a simulated TMAG5170-Q1 for host tests
+#[cfg(feature = "std")]
+pub mod sim;
+
+/// Implements recording and replaying of SPI traffic
+#[cfg(feature = "std")]
+pub mod capture;
+
+/// Implements decoding of captured SPI frames
+#[cfg(feature = "std")]
+pub mod decode;
+
+mod error;
+
+#[cfg(feature = "serde")]
+mod serde_fields;
+
+#[cfg(feature = "defmt")]
+mod defmt_format;
+
+pub use array::Tmag5170Array;
+pub use error::ExtError;
+
+use retry::{Backoff, NoBackoff, RetryCounters, RetryPolicy};
+use validation::{ConfigSet, Issues, ValidationMode};
+
 /// SPI mode
 pub const MODE: Mode = embedded_hal::spi::MODE_0;
 
+/// TEMP_RESULT code at 25°C
+const TEMP_CODE_25C: f32 = 17522.0;
+
+/// TEMP_RESULT codes per °C
+const TEMP_CODES_PER_C: f32 = 60.1;
+
+/// Converts a TEMP_RESULT code to °C
+pub fn temp_celsius(code: u16) -> f32 {
+    25.0 + (code as f32 - TEMP_CODE_25C) / TEMP_CODES_PER_C
+}
+
+/// Converts a temperature in °C to the TEMP_RESULT code
+pub fn temp_code(celsius: f32) -> u16 {
+    libm::roundf(TEMP_CODE_25C + (celsius - 25.0) * TEMP_CODES_PER_C).clamp(0.0, 65535.0) as u16
+}
+
 /// TMAG5170-Q1 driver
-pub struct Tmag5170<SPI, CS> {
+pub struct Tmag5170<SPI, CS, B = NoBackoff> {
     spi: SPI,
     cs: CS,
     crc: Crc<u8>, // TODO: to move crc table outside of tmag instance
+    shadow: ConfigSet,
+    retry: RetryPolicy,
+    retry_counters: RetryCounters,
+    backoff: B,
+    reset: reset::ResetState,
+    last_status: frame::FrameStatus,
+    data_type: system_config::DataType,
+    write_verify: bool,
+    #[cfg(feature = "link-stats")]
+    stats: stats::LinkStats,
 }
 
-/// TMAG5170-Q1 error type
-pub enum ExtError<E> {
-    /// Generic SPI error
-    E(E),
-
-    /// TMAG5170-Q1 CRC Error
-    CrcError,
-}
-
-impl<SPI, CS, E> Tmag5170<SPI, CS>
+impl<SPI, CS, E, PE> Tmag5170<SPI, CS>
 where
     SPI: Transfer<u8, Error = E>,
-    CS: OutputPin,
+    CS: OutputPin<Error = PE>,
 {
     /// Creates a new driver from a SPI peripheral and a NCS pin
     pub fn new(spi: SPI, cs: CS) -> Self {
         let crc = Crc::<u8>::new(0x03, 4, 0x0f, 0x00, false);
-        Tmag5170 { spi, cs, crc }
+        Tmag5170 {
+            spi,
+            cs,
+            crc,
+            shadow: ConfigSet::default(),
+            retry: RetryPolicy::new(),
+            retry_counters: RetryCounters::default(),
+            backoff: NoBackoff,
+            reset: reset::ResetState::default(),
+            last_status: frame::FrameStatus::from_u16(0),
+            data_type: system_config::DataType::Default,
+            write_verify: false,
+            #[cfg(feature = "link-stats")]
+            stats: stats::LinkStats::default(),
+        }
+    }
+}
+
+impl<SPI, CS, B, E, PE> Tmag5170<SPI, CS, B>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    B: Backoff,
+{
+    /// Replaces the backoff used between retry attempts
+    pub fn with_backoff<B2: Backoff>(self, backoff: B2) -> Tmag5170<SPI, CS, B2> {
+        Tmag5170 {
+            spi: self.spi,
+            cs: self.cs,
+            crc: self.crc,
+            shadow: self.shadow,
+            retry: self.retry,
+            retry_counters: self.retry_counters,
+            backoff,
+            reset: self.reset,
+            last_status: self.last_status,
+            data_type: self.data_type,
+            write_verify: self.write_verify,
+            #[cfg(feature = "link-stats")]
+            stats: self.stats,
+        }
     }
 
-    fn write_register(&mut self, reg: Register, value: u16, cmd: u8) -> Result<(), ExtError<E>> {
-        self.crc.init();
+    /// Releases the SPI peripheral and the NCS pin
+    pub fn free(self) -> (SPI, CS) {
+        (self.spi, self.cs)
+    }
+
+    /// Returns the status bits of the last SDO frame with a valid CRC
+    pub fn frame_status(&self) -> frame::FrameStatus {
+        self.last_status
+    }
+
+    /// Returns a snapshot of the SPI link quality counters
+    #[cfg(feature = "link-stats")]
+    pub fn link_stats(&self) -> stats::LinkStats {
+        self.stats
+    }
+
+    /// Resets the SPI link quality counters
+    #[cfg(feature = "link-stats")]
+    pub fn reset_link_stats(&mut self) {
+        self.stats = stats::LinkStats::default();
+    }
+
+    /// Read back every register write and compare its writable bits. Writes while DATA_TYPE
+    /// selects special frames and writes entering deep sleep are not read back
+    pub fn set_write_verify(&mut self, write_verify: bool) {
+        self.write_verify = write_verify;
+    }
 
-        let mut buffer: [u8; 4] = [0; 4];
-        let value_bytes = value.to_be_bytes();
+    /// Sets the retry policy of register and special reads and writes. Frames with
+    /// a command, e.g. a conversion start, are never repeated
+    pub fn set_retry_policy(&mut self, retry: RetryPolicy) {
+        self.retry = retry;
+    }
 
-        buffer[0] = reg.addr();
-        buffer[1] = value_bytes[0];
-        buffer[2] = value_bytes[1];
-        buffer[3] = cmd << 4;
+    /// Returns the number of retries done so far
+    pub fn retry_counters(&self) -> RetryCounters {
+        self.retry_counters
+    }
 
-        self.crc.update(&buffer);
-        let crc4 = self.crc.finish();
-        buffer[3] |= crc4;
+    /// Resets the retry counters
+    pub fn reset_retry_counters(&mut self) {
+        self.retry_counters = RetryCounters::default();
+    }
 
-        let _ = self.cs.set_low();
-        let res = self.spi.transfer(&mut buffer);
-        let _ = self.cs.set_high();
-        match res {
-            Ok(_buf) => (),
-            Err(e) => return Err(ExtError::E(e)),
+    fn with_retry<T>(
+        &mut self,
+        cmd: u8,
+        mut op: impl FnMut(&mut Self) -> Result<T, ExtError<E, PE>>,
+    ) -> Result<T, ExtError<E, PE>> {
+        // A repeated command frame would run the command twice, e.g. start two conversions
+        let max_attempts = if cmd == 0 {
+            self.retry.max_attempts()
+        } else {
+            1
         };
+        let mut attempt = 1;
+        loop {
+            match op(self) {
+                Err(e) if attempt < max_attempts && self.retry.is_retryable(&e) => {
+                    self.retry_counters.record(&e);
+                    #[cfg(feature = "link-stats")]
+                    stats::bump(&mut self.stats.retries);
+                    self.backoff.backoff(self.retry.backoff_us(attempt));
+                    attempt += 1;
+                }
+                Err(e) => {
+                    if attempt > 1 {
+                        self.retry_counters.exhausted =
+                            self.retry_counters.exhausted.saturating_add(1);
+                    }
+                    return Err(e);
+                }
+                res => return res,
+            }
+        }
+    }
+
+    fn transfer(&mut self, buffer: &mut [u8; 4]) -> Result<(), ExtError<E, PE>> {
+        let res = self.transfer_frame(buffer);
+
+        #[cfg(feature = "link-stats")]
+        {
+            stats::bump(&mut self.stats.frames);
+            match res {
+                Err(ExtError::Crc) => stats::bump(&mut self.stats.crc_errors),
+                Err(ExtError::Spi(_)) => stats::bump(&mut self.stats.spi_errors),
+                Err(ExtError::Pin(_)) => stats::bump(&mut self.stats.pin_errors),
+                _ => (),
+            }
+        }
 
-        let packet_crc4 = buffer[3] & 0x0f; // save crc
-        buffer[3] &= 0xf0; // erase crc in buffer
-        self.crc.init();
-        self.crc.update(&buffer);
-        let calc_crc4 = self.crc.finish();
+        res
+    }
+
+    fn transfer_frame(&mut self, buffer: &mut [u8; 4]) -> Result<(), ExtError<E, PE>> {
+        self.cs.set_low().map_err(ExtError::Pin)?;
+        let res = self.spi.transfer(buffer);
+        let cs_res = self.cs.set_high();
+        res.map_err(ExtError::Spi)?;
+        cs_res.map_err(ExtError::Pin)?;
 
-        if packet_crc4 == calc_crc4 {
+        if frame::is_blank(buffer) {
+            return Err(ExtError::NotPresent);
+        }
+
+        if frame::crc_ok(&mut self.crc, buffer) {
             Ok(())
         } else {
-            Err(ExtError::CrcError)
+            Err(ExtError::Crc)
         }
     }
 
-    fn read_register(&mut self, reg: Register, cmd: u8) -> Result<u16, ExtError<E>> {
-        self.crc.init();
-
-        let mut buffer: [u8; 4] = [0; 4];
+    fn write_register(
+        &mut self,
+        reg: Register,
+        value: u16,
+        cmd: u8,
+    ) -> Result<(), ExtError<E, PE>> {
+        self.with_retry(cmd, |tmag| tmag.write_register_once(reg, value, cmd))?;
+
+        // Special SDO frames carry no register value and a deep sleeping device does not answer
+        let writable = reg.writable_bits();
+        if self.write_verify
+            && writable != 0
+            && self.data_type == system_config::DataType::Default
+            && !Self::enters_deep_sleep(reg, value)
+        {
+            let actual = self.read_register(reg, 0x00)?;
+            let bits = (actual ^ value) & writable;
+            if bits != 0 {
+                return Err(ExtError::Mismatch {
+                    register: reg,
+                    expected: value & writable,
+                    actual: actual & writable,
+                    bits,
+                });
+            }
+        }
 
-        buffer[0] = reg.addr() | 0x80; // Set MSB to indicate read operation
-        buffer[1] = 0;
-        buffer[2] = 0;
-        buffer[3] = cmd << 4;
+        Ok(())
+    }
 
-        self.crc.update(&buffer);
-        let crc4 = self.crc.finish();
-        buffer[3] |= crc4;
+    fn write_register_once(
+        &mut self,
+        reg: Register,
+        value: u16,
+        cmd: u8,
+    ) -> Result<(), ExtError<E, PE>> {
+        let mut buffer = frame::request(&mut self.crc, reg.addr(), value, cmd);
+        let res = self.transfer(&mut buffer);
+        #[cfg(feature = "log")]
+        trace::write(reg, value, cmd, &buffer, &res, self.data_type);
+        res?;
+        self.record_status(&buffer);
+
+        // The next frame would only wake the device up again
+        if Self::enters_deep_sleep(reg, value) {
+            return Ok(());
+        }
 
-        let _ = self.cs.set_low();
-        let res = self.spi.transfer(&mut buffer);
-        let _ = self.cs.set_high();
-        match res {
-            Ok(_buf) => (),
-            Err(e) => return Err(ExtError::E(e)),
+        let data_type = match reg {
+            Register::SYSTEM_CONFIG => system_config::SystemConfig::form_u16(value).data_type(),
+            _ => self.data_type,
         };
+        if !self.retry.confirms_frames() {
+            self.data_type = data_type;
+            return Ok(());
+        }
+        self.confirm(reg, value, data_type)
+    }
+
+    fn enters_deep_sleep(reg: Register, value: u16) -> bool {
+        reg == Register::DEVICE_CONFIG
+            && device_config::DeviceConfig::form_u16(value).operating_mode()
+                == Some(device_config::OperatingMode::DeepSleep)
+    }
 
-        let packet_crc4 = buffer[3] & 0x0f; // save crc
-        buffer[3] &= 0xf0; // erase crc in buffer
-        self.crc.init();
-        self.crc.update(&buffer);
-        let calc_crc4 = self.crc.finish();
+    fn read_register(&mut self, reg: Register, cmd: u8) -> Result<u16, ExtError<E, PE>> {
+        let value = self.with_retry(cmd, |tmag| tmag.read_register_once(reg, cmd))?;
+        self.auto_reconfigure()?;
 
-        if packet_crc4 == calc_crc4 {
-            let value = ((buffer[1] as u16) << 8) + buffer[2] as u16;
-            Ok(value)
-        } else {
-            Err(ExtError::CrcError)
+        Ok(value)
+    }
+
+    fn read_register_once(&mut self, reg: Register, cmd: u8) -> Result<u16, ExtError<E, PE>> {
+        // Set MSB to indicate read operation
+        let mut buffer = frame::request(&mut self.crc, reg.addr() | 0x80, 0, cmd);
+        let res = self.transfer(&mut buffer);
+        #[cfg(feature = "log")]
+        trace::read(reg, cmd, &buffer, &res, self.data_type);
+        res?;
+        self.record_status(&buffer);
+
+        let value = frame::register_value(&buffer);
+        #[cfg(feature = "link-stats")]
+        if let Register::SYS_STATUS = reg {
+            self.stats
+                .record_sys_status(status::SysStatus::from_u16(value));
         }
+
+        if cmd != 0 && self.retry.confirms_frames() {
+            self.confirm(reg, value, self.data_type)?;
+        }
+
+        Ok(value)
     }
 
-    fn read_special(&mut self, cmd: u8) -> Result<(u16, u16), ExtError<E>> {
-        self.crc.init();
+    fn read_special(&mut self, cmd: u8) -> Result<(u16, u16), ExtError<E, PE>> {
+        let values = self.with_retry(cmd, |tmag| tmag.read_special_once(cmd))?;
+        self.auto_reconfigure()?;
 
-        let mut buffer: [u8; 4] = [0; 4];
+        Ok(values)
+    }
 
-        buffer[0] = 0x80; // Set MSB to indicate read operation
-        buffer[1] = 0;
-        buffer[2] = 0;
-        buffer[3] = cmd << 4;
+    fn read_special_once(&mut self, cmd: u8) -> Result<(u16, u16), ExtError<E, PE>> {
+        // Set MSB to indicate read operation
+        let mut buffer = frame::request(&mut self.crc, 0x80, 0, cmd);
+        let res = self.transfer(&mut buffer);
+        #[cfg(feature = "log")]
+        trace::special(cmd, &buffer, &res, self.data_type);
+        res?;
+        self.record_status(&buffer);
+
+        if cmd != 0 && self.retry.confirms_frames() {
+            let device = self.shadow.device.to_u16();
+            self.confirm(Register::DEVICE_CONFIG, device, self.data_type)?;
+        }
 
-        self.crc.update(&buffer);
-        let crc4 = self.crc.finish();
-        buffer[3] |= crc4;
+        Ok(frame::special_values(&buffer))
+    }
 
-        let _ = self.cs.set_low();
-        let res = self.spi.transfer(&mut buffer);
-        let _ = self.cs.set_high();
-        match res {
-            Ok(_buf) => (),
-            Err(e) => return Err(ExtError::E(e)),
-        };
+    /// Status of a SDO frame in the format selected by DATA_TYPE
+    fn parse_status(&self, buffer: &[u8; 4]) -> frame::FrameStatus {
+        frame::parse_status(buffer, self.data_type)
+    }
 
-        let packet_crc4 = buffer[3] & 0x0f; // save crc
-        buffer[3] &= 0xf0; // erase crc in buffer
-        self.crc.init();
-        self.crc.update(&buffer);
-        let calc_crc4 = self.crc.finish();
+    fn record_status(&mut self, buffer: &[u8; 4]) {
+        let status = self.parse_status(buffer);
+        self.last_status = status;
+        self.reset.seen |= status.cfg_reset();
+        // PREV_CRC_STAT refers to the frame before. Writes and commands are confirmed by a
+        // frame of their own when the retry policy retries frame errors
+        #[cfg(feature = "link-stats")]
+        if status.prev_crc_error() {
+            stats::bump(&mut self.stats.rejected_frames);
+        }
+    }
 
-        if packet_crc4 == calc_crc4 {
-            let ch1 = ((buffer[1] as u16) << 4) + (buffer[2] & 0x0f) as u16;
-            let ch2 = ((buffer[0] as u16) << 4) + (buffer[2] >> 4) as u16;
-            Ok((ch1, ch2))
+    /// PREV_CRC_STAT of the following frame tells whether a write or command was executed,
+    /// so a read of `reg` without command follows it if the retry policy asks for it.
+    /// `value` is the value `reg` holds if the frame was executed, `data_type` the DATA_TYPE
+    /// in effect afterwards
+    fn confirm(
+        &mut self,
+        reg: Register,
+        value: u16,
+        data_type: system_config::DataType,
+    ) -> Result<(), ExtError<E, PE>> {
+        let before = self.data_type;
+        // The frame is answered in the new format if the write was executed
+        #[cfg(feature = "log")]
+        let format = |executed| if executed { data_type } else { before };
+
+        let mut buffer = frame::request(&mut self.crc, reg.addr() | 0x80, 0, 0x00);
+        let res = self.transfer(&mut buffer);
+        let executed = match res {
+            Ok(()) => frame::executed(&buffer, reg, value, before, data_type),
+            Err(_) => Some(false),
+        };
+        #[cfg(feature = "log")]
+        trace::read(reg, 0x00, &buffer, &res, format(executed == Some(true)));
+        res?;
+
+        let executed = match executed {
+            Some(executed) => executed,
+            None => {
+                let mut again = frame::request(&mut self.crc, reg.addr() | 0x80, 0, 0x00);
+                let res = self.transfer(&mut again);
+                let executed = res.is_ok() && frame::prev_crc_error(&again, before);
+                #[cfg(feature = "log")]
+                trace::read(reg, 0x00, &again, &res, format(executed));
+                res?;
+                buffer = again;
+                executed
+            }
+        };
+        if executed {
+            self.data_type = data_type;
+        }
+        let status = self.parse_status(&buffer);
+        self.last_status = status;
+        self.reset.seen |= status.cfg_reset();
+
+        if executed {
+            Ok(())
         } else {
-            Err(ExtError::CrcError)
+            #[cfg(feature = "link-stats")]
+            stats::bump(&mut self.stats.rejected_frames);
+            Err(ExtError::Frame(status))
         }
     }
 
     /// Reads TMAG5170-Q1 magnetic registers in raw format. Returns (x,y,z)
-    pub fn read_mag_registers(&mut self) -> Result<(i16, i16, i16), ExtError<E>> {
+    pub fn read_mag_registers(&mut self) -> Result<(i16, i16, i16), ExtError<E, PE>> {
         let x = self.read_register(Register::X_CH_RESULT, 0x00)?;
         let y = self.read_register(Register::Y_CH_RESULT, 0x00)?;
         let z = self.read_register(Register::Z_CH_RESULT, 0x00)?;
@@ -175,7 +516,7 @@ where
     }
 
     /// Reads TMAG5170-Q1 angle and magnitude registers in raw format. Returns (angle,magnitude)
-    pub fn read_angle_registers(&mut self) -> Result<(i16, i16), ExtError<E>> {
+    pub fn read_angle_registers(&mut self) -> Result<(i16, i16), ExtError<E, PE>> {
         let a = self.read_register(Register::ANGLE_RESULT, 0x00)?;
         let m = self.read_register(Register::MAGNITUDE_RESULT, 0x00)?;
 
@@ -183,49 +524,75 @@ where
     }
 
     /// Reads TMAG5170-Q1 temp registers in raw format.
-    pub fn read_temp_register(&mut self) -> Result<i16, ExtError<E>> {
+    pub fn read_temp_register(&mut self) -> Result<i16, ExtError<E, PE>> {
         let t = self.read_register(Register::TEMP_RESULT, 0x00)?;
 
         Ok(t as i16)
     }
 
     /// Reads TMAG5170-Q1 angle and magnitude registers in raw format as special read. Returns (angle,magnitude)
-    pub fn read_am(&mut self) -> Result<(u16, u16), ExtError<E>> {
+    pub fn read_am(&mut self) -> Result<(u16, u16), ExtError<E, PE>> {
         let (a, m) = self.read_special(0x00)?;
 
         Ok((a, m))
     }
 
     /// Reads TMAG5170-Q1 CONV_STATUS register in raw format.
-    pub fn read_conv_status_register(&mut self) -> Result<i16, ExtError<E>> {
+    pub fn read_conv_status_register(&mut self) -> Result<i16, ExtError<E, PE>> {
         let s = self.read_register(Register::CONV_STATUS, 0x00)?;
 
         Ok(s as i16)
     }
 
     /// Reads TMAG5170-Q1 AFE_STATUS register in raw format.
-    pub fn read_afe_status_register(&mut self) -> Result<i16, ExtError<E>> {
+    pub fn read_afe_status_register(&mut self) -> Result<i16, ExtError<E, PE>> {
         let s = self.read_register(Register::AFE_STATUS, 0x00)?;
 
         Ok(s as i16)
     }
 
     /// Reads TMAG5170-Q1 SYS_STATUS register in raw format.
-    pub fn read_sys_status_register(&mut self) -> Result<i16, ExtError<E>> {
+    pub fn read_sys_status_register(&mut self) -> Result<i16, ExtError<E, PE>> {
         let s = self.read_register(Register::SYS_STATUS, 0x00)?;
 
         Ok(s as i16)
     }
 
+    /// Reads TMAG5170-Q1 CONV_STATUS register.
+    pub fn read_conv_status(&mut self) -> Result<status::ConvStatus, ExtError<E, PE>> {
+        let s = self.read_register(Register::CONV_STATUS, 0x00)?;
+
+        Ok(status::ConvStatus::from_u16(s))
+    }
+
+    /// Reads TMAG5170-Q1 AFE_STATUS register.
+    pub fn read_afe_status(&mut self) -> Result<status::AfeStatus, ExtError<E, PE>> {
+        let s = self.read_register(Register::AFE_STATUS, 0x00)?;
+
+        Ok(status::AfeStatus::from_u16(s))
+    }
+
+    /// Reads TMAG5170-Q1 SYS_STATUS register.
+    pub fn read_sys_status(&mut self) -> Result<status::SysStatus, ExtError<E, PE>> {
+        let s = self.read_register(Register::SYS_STATUS, 0x00)?;
+
+        Ok(status::SysStatus::from_u16(s))
+    }
+
     /// Reads TMAG5170-Q1 TEST_CONFIG register in raw format.
-    pub fn read_test_config_register(&mut self) -> Result<i16, ExtError<E>> {
+    pub fn read_test_config_register(&mut self) -> Result<i16, ExtError<E, PE>> {
         let tc = self.read_register(Register::TEST_CONFIG, 0x00)?;
 
         Ok(tc as i16)
     }
 
+    /// Reads any TMAG5170-Q1 register in raw format.
+    pub fn read_raw_register(&mut self, reg: Register) -> Result<u16, ExtError<E, PE>> {
+        self.read_register(reg, 0x00)
+    }
+
     /// Apply  TMAG5170-Q1 AlertConfig
-    pub fn conv_start(&mut self) -> Result<(), ExtError<E>> {
+    pub fn conv_start(&mut self) -> Result<(), ExtError<E, PE>> {
         self.read_register(Register::DEVICE_CONFIG, 0x01)?;
 
         Ok(())
@@ -235,8 +602,9 @@ where
     pub fn apply_device_config(
         &mut self,
         config: device_config::DeviceConfig,
-    ) -> Result<(), ExtError<E>> {
+    ) -> Result<(), ExtError<E, PE>> {
         self.write_register(Register::DEVICE_CONFIG, config.to_u16(), 0x00)?;
+        self.shadow.device = config;
 
         Ok(())
     }
@@ -244,8 +612,9 @@ where
     pub fn apply_sensor_config(
         &mut self,
         config: sensor_config::SensorConfig,
-    ) -> Result<(), ExtError<E>> {
+    ) -> Result<(), ExtError<E, PE>> {
         self.write_register(Register::SENSOR_CONFIG, config.to_u16(), 0x00)?;
+        self.shadow.sensor = config;
 
         Ok(())
     }
@@ -254,8 +623,9 @@ where
     pub fn apply_system_config(
         &mut self,
         config: system_config::SystemConfig,
-    ) -> Result<(), ExtError<E>> {
+    ) -> Result<(), ExtError<E, PE>> {
         self.write_register(Register::SYSTEM_CONFIG, config.to_u16(), 0x00)?;
+        self.shadow.system = config;
 
         Ok(())
     }
@@ -264,42 +634,177 @@ where
     pub fn apply_alert_config(
         &mut self,
         config: alert_config::AlertConfig,
-    ) -> Result<(), ExtError<E>> {
+    ) -> Result<(), ExtError<E, PE>> {
         self.write_register(Register::ALERT_CONFIG, config.to_u16(), 0x00)?;
+        self.shadow.alert = config;
 
         Ok(())
     }
+
+    /// Validate and apply all TMAG5170-Q1 configuration registers.
+    /// DEVICE_CONFIG is written last, so conversions start with the rest of the configuration in place
+    pub fn apply_config(
+        &mut self,
+        config: ConfigSet,
+        mode: ValidationMode,
+    ) -> Result<Issues, ExtError<E, PE>> {
+        let issues = config.validate();
+        if let (ValidationMode::Reject, Some(issue)) = (mode, issues.first()) {
+            return Err(ExtError::InvalidConfig(issue));
+        }
+
+        self.apply_sensor_config(config.sensor)?;
+        self.apply_system_config(config.system)?;
+        self.apply_alert_config(config.alert)?;
+        self.apply_device_config(config.device)?;
+
+        Ok(issues)
+    }
+
+    /// Returns the configuration last written by the driver
+    pub fn config(&self) -> ConfigSet {
+        self.shadow
+    }
 }
 
+/// TMAG5170-Q1 register map
 #[allow(dead_code)]
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
-enum Register {
-    // TODO: Made registers real
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Register {
+    /// Device operation modes
     DEVICE_CONFIG = 0x00,
+
+    /// Sensor channels, ranges and angle calculation
     SENSOR_CONFIG = 0x01,
+
+    /// Diagnostics, trigger and data type
     SYSTEM_CONFIG = 0x02,
+
+    /// ALERT pin function
     ALERT_CONFIG = 0x03,
+
+    /// X axis thresholds
     X_THRX_CONFIG = 0x04,
+
+    /// Y axis thresholds
     Y_THRX_CONFIG = 0x05,
+
+    /// Z axis thresholds
     Z_THRX_CONFIG = 0x06,
+
+    /// Temperature thresholds
     T_THRX_CONFIG = 0x07,
+
+    /// Conversion status
     CONV_STATUS = 0x08,
+
+    /// X axis result
     X_CH_RESULT = 0x09,
+
+    /// Y axis result
     Y_CH_RESULT = 0x0A,
+
+    /// Z axis result
     Z_CH_RESULT = 0x0B,
+
+    /// Temperature result
     TEMP_RESULT = 0x0C,
+
+    /// Analog front end status
     AFE_STATUS = 0x0D,
+
+    /// System status
     SYS_STATUS = 0x0E,
+
+    /// Test configuration and device version
     TEST_CONFIG = 0x0F,
+
+    /// Oscillator monitor
     OSC_MONITOR = 0x10,
+
+    /// Magnetic gain
     MAG_GAIN_CONFIG = 0x11,
+
+    /// Angle result
     ANGLE_RESULT = 0x13,
+
+    /// Magnitude result
     MAGNITUDE_RESULT = 0x14,
 }
 
 impl Register {
+    /// All registers in address order
+    pub const ALL: [Register; 20] = [
+        Register::DEVICE_CONFIG,
+        Register::SENSOR_CONFIG,
+        Register::SYSTEM_CONFIG,
+        Register::ALERT_CONFIG,
+        Register::X_THRX_CONFIG,
+        Register::Y_THRX_CONFIG,
+        Register::Z_THRX_CONFIG,
+        Register::T_THRX_CONFIG,
+        Register::CONV_STATUS,
+        Register::X_CH_RESULT,
+        Register::Y_CH_RESULT,
+        Register::Z_CH_RESULT,
+        Register::TEMP_RESULT,
+        Register::AFE_STATUS,
+        Register::SYS_STATUS,
+        Register::TEST_CONFIG,
+        Register::OSC_MONITOR,
+        Register::MAG_GAIN_CONFIG,
+        Register::ANGLE_RESULT,
+        Register::MAGNITUDE_RESULT,
+    ];
+
     fn addr(self) -> u8 {
         self as u8
     }
+
+    /// Register at an address, None for unused addresses
+    pub fn from_addr(addr: u8) -> Option<Self> {
+        let reg = match addr {
+            0x00 => Register::DEVICE_CONFIG,
+            0x01 => Register::SENSOR_CONFIG,
+            0x02 => Register::SYSTEM_CONFIG,
+            0x03 => Register::ALERT_CONFIG,
+            0x04 => Register::X_THRX_CONFIG,
+            0x05 => Register::Y_THRX_CONFIG,
+            0x06 => Register::Z_THRX_CONFIG,
+            0x07 => Register::T_THRX_CONFIG,
+            0x08 => Register::CONV_STATUS,
+            0x09 => Register::X_CH_RESULT,
+            0x0A => Register::Y_CH_RESULT,
+            0x0B => Register::Z_CH_RESULT,
+            0x0C => Register::TEMP_RESULT,
+            0x0D => Register::AFE_STATUS,
+            0x0E => Register::SYS_STATUS,
+            0x0F => Register::TEST_CONFIG,
+            0x10 => Register::OSC_MONITOR,
+            0x11 => Register::MAG_GAIN_CONFIG,
+            0x13 => Register::ANGLE_RESULT,
+            0x14 => Register::MAGNITUDE_RESULT,
+            _ => return None,
+        };
+        Some(reg)
+    }
+
+    /// Bits which can be written and read back. Reserved and read only bits are cleared
+    pub fn writable_bits(self) -> u16 {
+        match self {
+            Register::DEVICE_CONFIG => !device_config::DeviceConfig::RESERVED_BITS,
+            Register::SENSOR_CONFIG => !sensor_config::SensorConfig::RESERVED_BITS,
+            Register::SYSTEM_CONFIG => !system_config::SystemConfig::RESERVED_BITS,
+            Register::ALERT_CONFIG => !alert_config::AlertConfig::RESERVED_BITS,
+            Register::X_THRX_CONFIG
+            | Register::Y_THRX_CONFIG
+            | Register::Z_THRX_CONFIG
+            | Register::T_THRX_CONFIG => 0xffff,
+            Register::TEST_CONFIG => !test_config::TestConfig::RESERVED_BITS & !(0b11 << 4),
+            Register::MAG_GAIN_CONFIG => 0xc7ff,
+            _ => 0x0000,
+        }
+    }
 }
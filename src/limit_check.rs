@@ -0,0 +1,108 @@
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::retry::Backoff;
+use crate::status::SysStatus;
+use crate::{ExtError, Tmag5170};
+
+/// Outcome of the hardware limit checks, true means the limit was violated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LimitCheck {
+    /// X channel limit check failed (XCH_THX)
+    pub x: bool,
+
+    /// Y channel limit check failed (YCH_THX)
+    pub y: bool,
+
+    /// Z channel limit check failed (ZCH_THX)
+    pub z: bool,
+
+    /// Temperature limit check failed (TEMP_THX)
+    pub t: bool,
+}
+
+impl LimitCheck {
+    /// Decodes the limit check bits of SYS_STATUS
+    pub fn from_sys_status(sys: SysStatus) -> Self {
+        LimitCheck {
+            x: sys.xch_thx(),
+            y: sys.ych_thx(),
+            z: sys.zch_thx(),
+            t: sys.temp_thx(),
+        }
+    }
+
+    /// Returns true if any limit was violated
+    pub fn any(&self) -> bool {
+        self.x || self.y || self.z || self.t
+    }
+}
+
+/// Raw magnetic and temperature results with the limit checks of the same conversion
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LimitedMeasurement {
+    /// X_CH_RESULT
+    pub x: i16,
+
+    /// Y_CH_RESULT
+    pub y: i16,
+
+    /// Z_CH_RESULT
+    pub z: i16,
+
+    /// TEMP_RESULT
+    pub t: i16,
+
+    /// Limit checks read from SYS_STATUS after the results
+    pub limits: LimitCheck,
+}
+
+impl<SPI, CS, B, E, PE> Tmag5170<SPI, CS, B>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    B: Backoff,
+{
+    /// Enables the hardware limit checks on top of the current SystemConfig and DeviceConfig
+    pub fn enable_limit_checks(
+        &mut self,
+        x: bool,
+        y: bool,
+        z: bool,
+        t: bool,
+    ) -> Result<(), ExtError<E, PE>> {
+        let config = self.config();
+
+        let system = config
+            .system
+            .set_t_x_limit_check(x)
+            .set_t_y_limit_check(y)
+            .set_t_z_limit_check(z);
+        self.apply_system_config(system)?;
+
+        let device = config.device.set_t_limit_check_en(t);
+        self.apply_device_config(device)?;
+
+        Ok(())
+    }
+
+    /// Reads the limit check results from SYS_STATUS
+    pub fn read_limit_check(&mut self) -> Result<LimitCheck, ExtError<E, PE>> {
+        let sys = self.read_sys_status()?;
+
+        Ok(LimitCheck::from_sys_status(sys))
+    }
+
+    /// Reads magnetic and temperature registers in raw format together with the limit checks
+    pub fn read_with_limits(&mut self) -> Result<LimitedMeasurement, ExtError<E, PE>> {
+        let (x, y, z) = self.read_mag_registers()?;
+        let t = self.read_temp_register()?;
+        let limits = self.read_limit_check()?;
+
+        Ok(LimitedMeasurement { x, y, z, t, limits })
+    }
+}
@@ -0,0 +1,58 @@
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::alert_config::AlertConfig;
+use crate::device_config::DeviceConfig;
+use crate::retry::Backoff;
+use crate::system_config::SystemConfig;
+use crate::test_config::{TestConfig, Version};
+use crate::{ExtError, Register, Tmag5170};
+
+impl<SPI, CS, B, E, PE> Tmag5170<SPI, CS, B>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    B: Backoff,
+{
+    /// Checks that a TMAG5170-Q1 answers on the bus and returns its version.
+    /// Blank frames return ExtError::NotPresent, reserved bits set or an unknown VER
+    /// return ExtError::WrongDevice
+    pub fn probe(&mut self) -> Result<Version, ExtError<E, PE>> {
+        let test_config = self.read_register(Register::TEST_CONFIG, 0x00)?;
+        check_signature(
+            Register::TEST_CONFIG,
+            test_config,
+            TestConfig::RESERVED_BITS,
+        )?;
+        let version = TestConfig::form_u16(test_config)
+            .ver()
+            .ok_or(ExtError::WrongDevice {
+                register: Register::TEST_CONFIG,
+                value: test_config,
+            })?;
+
+        let registers = [
+            (Register::DEVICE_CONFIG, DeviceConfig::RESERVED_BITS),
+            (Register::SYSTEM_CONFIG, SystemConfig::RESERVED_BITS),
+            (Register::ALERT_CONFIG, AlertConfig::RESERVED_BITS),
+        ];
+        for &(register, reserved) in registers.iter() {
+            let value = self.read_register(register, 0x00)?;
+            check_signature(register, value, reserved)?;
+        }
+
+        Ok(version)
+    }
+}
+
+fn check_signature<E, PE>(
+    register: Register,
+    value: u16,
+    reserved: u16,
+) -> Result<(), ExtError<E, PE>> {
+    if value & reserved != 0 {
+        return Err(ExtError::WrongDevice { register, value });
+    }
+
+    Ok(())
+}
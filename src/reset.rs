@@ -0,0 +1,158 @@
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::alert_config::AlertConfig;
+use crate::device_config::DeviceConfig;
+use crate::retry::Backoff;
+use crate::sensor_config::SensorConfig;
+use crate::system_config::{DataType, SystemConfig};
+use crate::{ExtError, Register, Tmag5170};
+
+/// How a device reset was detected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ResetSource {
+    /// CFG_RESET bit of a SDO frame status
+    FrameStatus,
+
+    /// CFG_RESET bit of AFE_STATUS
+    AfeStatus,
+
+    /// Configuration registers differ from the configuration written by the driver
+    ShadowMismatch,
+}
+
+/// Device reset followed by re-applying the last known configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ResetEvent {
+    /// How the reset was detected
+    pub source: ResetSource,
+}
+
+impl<SPI, CS, B, E, PE> Tmag5170<SPI, CS, B>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    B: Backoff,
+{
+    /// Re-apply the last known configuration as soon as a read reports CFG_RESET in its frame status
+    pub fn set_auto_reconfigure(&mut self, auto_reconfigure: bool) {
+        self.reset.auto_reconfigure = auto_reconfigure;
+    }
+
+    /// Returns the last reset event and clears it
+    pub fn take_reset_event(&mut self) -> Option<ResetEvent> {
+        self.reset.event.take()
+    }
+
+    /// Checks the frame status, AFE_STATUS and the configuration registers for a device reset.
+    /// While DATA_TYPE selects special frames only the frame status is checked.
+    /// The last known configuration is re-applied if a reset is detected
+    pub fn check_reset(&mut self) -> Result<Option<ResetEvent>, ExtError<E, PE>> {
+        // Detection reads must not trigger the automatic reconfiguration
+        self.reset.recovering = true;
+        let source = self.detect_reset();
+        self.reset.recovering = false;
+
+        match source? {
+            Some(source) => self.reconfigure(source).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn detect_reset(&mut self) -> Result<Option<ResetSource>, ExtError<E, PE>> {
+        let seen = self.reset.seen;
+        let afe = self.read_afe_status()?;
+
+        if seen {
+            Ok(Some(ResetSource::FrameStatus))
+        } else if self.data_type != DataType::Default {
+            // Special frames carry no register values, only CFG_RESET in their status
+            Ok(self.reset.seen.then_some(ResetSource::FrameStatus))
+        } else if afe.cfg_reset() {
+            Ok(Some(ResetSource::AfeStatus))
+        } else if !self.shadow_matches()? {
+            Ok(Some(ResetSource::ShadowMismatch))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn shadow_matches(&mut self) -> Result<bool, ExtError<E, PE>> {
+        let shadow = self.config();
+        let registers = [
+            (
+                Register::DEVICE_CONFIG,
+                shadow.device.to_u16(),
+                DeviceConfig::RESERVED_BITS,
+            ),
+            (
+                Register::SENSOR_CONFIG,
+                shadow.sensor.to_u16(),
+                SensorConfig::RESERVED_BITS,
+            ),
+            (
+                Register::SYSTEM_CONFIG,
+                shadow.system.to_u16(),
+                SystemConfig::RESERVED_BITS,
+            ),
+            (
+                Register::ALERT_CONFIG,
+                shadow.alert.to_u16(),
+                AlertConfig::RESERVED_BITS,
+            ),
+        ];
+
+        for &(reg, expected, reserved) in registers.iter() {
+            let actual = self.read_register(reg, 0x00)?;
+            if (actual ^ expected) & !reserved != 0 {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn reconfigure(&mut self, source: ResetSource) -> Result<ResetEvent, ExtError<E, PE>> {
+        self.reset.recovering = true;
+        let res = self.reapply_config();
+        self.reset.recovering = false;
+        res?;
+
+        let event = ResetEvent { source };
+        self.reset.event = Some(event);
+        Ok(event)
+    }
+
+    fn reapply_config(&mut self) -> Result<(), ExtError<E, PE>> {
+        // AFE_STATUS read clears CFG_RESET
+        self.read_afe_status()?;
+        self.reset.seen = false;
+
+        let shadow = self.config();
+        self.apply_sensor_config(shadow.sensor)?;
+        self.apply_system_config(shadow.system)?;
+        self.apply_alert_config(shadow.alert)?;
+        self.apply_device_config(shadow.device)?;
+
+        Ok(())
+    }
+
+    pub(crate) fn auto_reconfigure(&mut self) -> Result<(), ExtError<E, PE>> {
+        if self.reset.auto_reconfigure && self.reset.seen && !self.reset.recovering {
+            self.reconfigure(ResetSource::FrameStatus)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reset detection state of the driver
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ResetState {
+    pub(crate) seen: bool,
+    auto_reconfigure: bool,
+    recovering: bool,
+    event: Option<ResetEvent>,
+}
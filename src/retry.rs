@@ -0,0 +1,177 @@
+use embedded_hal::blocking::delay::DelayUs;
+
+use crate::ExtError;
+
+/// Which errors are retried and how many times
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u8,
+    retry_crc: bool,
+    retry_frame: bool,
+    retry_spi: bool,
+    confirm: bool,
+    backoff_us: u32,
+}
+
+impl RetryPolicy {
+    /// Creates default policy: a single attempt, CRC and frame errors are
+    /// retried once the number of attempts is raised
+    pub fn new() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            retry_crc: true,
+            retry_frame: true,
+            retry_spi: false,
+            confirm: false,
+            backoff_us: 0,
+        }
+    }
+
+    /// Set maximum number of attempts including the first one
+    pub fn set_max_attempts(mut self, max_attempts: u8) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Retry on CRC mismatch of the SDO frame
+    pub fn set_retry_crc(mut self, retry_crc: bool) -> Self {
+        self.retry_crc = retry_crc;
+        self
+    }
+
+    /// Retry when the device reports a corrupted SDI frame. Writes and commands are
+    /// then confirmed by an extra read, commands report the error without retry
+    pub fn set_retry_frame(mut self, retry_frame: bool) -> Self {
+        self.retry_frame = retry_frame;
+        self
+    }
+
+    /// Retry on SPI bus errors
+    pub fn set_retry_spi(mut self, retry_spi: bool) -> Self {
+        self.retry_spi = retry_spi;
+        self
+    }
+
+    /// Confirm writes and commands with an extra read even without retries, a frame the
+    /// device ignored is returned as ExtError::Frame
+    pub fn set_confirm(mut self, confirm: bool) -> Self {
+        self.confirm = confirm;
+        self
+    }
+
+    /// Set delay before a retry, multiplied by the number of failed attempts
+    pub fn set_backoff_us(mut self, backoff_us: u32) -> Self {
+        self.backoff_us = backoff_us;
+        self
+    }
+
+    /// Maximum number of attempts including the first one
+    pub fn max_attempts(&self) -> u8 {
+        self.max_attempts
+    }
+
+    /// Delay before the retry following `attempt` failed attempts
+    pub fn backoff_us(&self, attempt: u8) -> u32 {
+        self.backoff_us.saturating_mul(attempt as u32)
+    }
+
+    /// Writes and commands are followed by a read which checks PREV_CRC_STAT
+    pub(crate) fn confirms_frames(&self) -> bool {
+        self.confirm || (self.max_attempts > 1 && self.retry_frame)
+    }
+
+    /// Returns true if the error is retried by this policy
+    pub fn is_retryable<E, PE>(&self, error: &ExtError<E, PE>) -> bool {
+        match error {
+            ExtError::Crc => self.retry_crc,
+            ExtError::Frame(_) => self.retry_frame,
+            ExtError::Spi(_) => self.retry_spi,
+            _ => false,
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new()
+    }
+}
+
+/// Number of retries done by the driver
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RetryCounters {
+    /// Retries after a CRC mismatch
+    pub crc: u32,
+
+    /// Retries after a frame error reported by the device
+    pub frame: u32,
+
+    /// Retries after a SPI bus error
+    pub spi: u32,
+
+    /// Operations which failed after all attempts
+    pub exhausted: u32,
+}
+
+impl RetryCounters {
+    /// Total number of retries
+    pub fn retries(&self) -> u32 {
+        self.crc.saturating_add(self.frame).saturating_add(self.spi)
+    }
+
+    pub(crate) fn record<E, PE>(&mut self, error: &ExtError<E, PE>) {
+        let counter = match error {
+            ExtError::Crc => &mut self.crc,
+            ExtError::Frame(_) => &mut self.frame,
+            ExtError::Spi(_) => &mut self.spi,
+            _ => return,
+        };
+        *counter = counter.saturating_add(1);
+    }
+}
+
+/// Waits between retry attempts
+pub trait Backoff {
+    /// Blocks for `us` microseconds
+    fn backoff(&mut self, us: u32);
+}
+
+/// Retries immediately
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoBackoff;
+
+impl Backoff for NoBackoff {
+    fn backoff(&mut self, _us: u32) {}
+}
+
+/// Waits between retry attempts using a delay provider
+pub struct DelayBackoff<D> {
+    delay: D,
+}
+
+impl<D> DelayBackoff<D>
+where
+    D: DelayUs<u32>,
+{
+    /// Creates backoff from a delay provider
+    pub fn new(delay: D) -> Self {
+        DelayBackoff { delay }
+    }
+
+    /// Releases the delay provider
+    pub fn free(self) -> D {
+        self.delay
+    }
+}
+
+impl<D> Backoff for DelayBackoff<D>
+where
+    D: DelayUs<u32>,
+{
+    fn backoff(&mut self, us: u32) {
+        if us > 0 {
+            self.delay.delay_us(us);
+        }
+    }
+}
@@ -0,0 +1,495 @@
+use std::f64::consts::PI;
+
+use crate::device_config::{ConvAvg, DeviceConfig};
+use crate::sensor_config::{AngleEn, Range, SensorConfig};
+use crate::test_config::Version;
+use crate::validation::Axis;
+
+/// Time of one conversion of one channel without averaging, in seconds
+pub const SAMPLE_PERIOD: f64 = 25e-6;
+
+/// Magnetic field in mT and die temperature in °C at one instant
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sample {
+    /// X component in mT
+    pub x: f32,
+
+    /// Y component in mT
+    pub y: f32,
+
+    /// Z component in mT
+    pub z: f32,
+
+    /// Temperature in °C
+    pub temperature: f32,
+}
+
+impl Sample {
+    /// Creates sample from the field components in mT and the temperature in °C
+    pub fn new(x: f32, y: f32, z: f32, temperature: f32) -> Self {
+        Sample {
+            x,
+            y,
+            z,
+            temperature,
+        }
+    }
+
+    fn lerp(self, other: Sample, ratio: f32) -> Sample {
+        Sample {
+            x: self.x + (other.x - self.x) * ratio,
+            y: self.y + (other.y - self.y) * ratio,
+            z: self.z + (other.z - self.z) * ratio,
+            temperature: self.temperature + (other.temperature - self.temperature) * ratio,
+        }
+    }
+}
+
+/// Time-varying field and temperature
+pub trait Scenario {
+    /// Field and temperature at `t` seconds
+    fn sample(&mut self, t: f64) -> Sample;
+}
+
+/// Diametric magnet rotating above the sensor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RotatingMagnet {
+    amplitude: f32,
+    frequency: f64,
+    phase: f64,
+    plane: AngleEn,
+    temperature: f32,
+}
+
+impl RotatingMagnet {
+    /// Creates magnet with the field amplitude in mT turning at `frequency` revolutions per second
+    /// in the XY plane
+    pub fn new(amplitude: f32, frequency: f64) -> Self {
+        RotatingMagnet {
+            amplitude,
+            frequency,
+            phase: 0.0,
+            plane: AngleEn::Xy,
+            temperature: 25.0,
+        }
+    }
+
+    /// Set angle at t = 0 in degrees
+    pub fn set_phase(mut self, degrees: f64) -> Self {
+        self.phase = degrees.to_radians();
+        self
+    }
+
+    /// Set rotation plane, the angle is measured from the first axis. NoAngle is taken as Xy
+    pub fn set_plane(mut self, plane: AngleEn) -> Self {
+        self.plane = plane;
+        self
+    }
+
+    /// Set temperature in °C
+    pub fn set_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+}
+
+impl Scenario for RotatingMagnet {
+    fn sample(&mut self, t: f64) -> Sample {
+        let angle = 2.0 * PI * self.frequency * t + self.phase;
+        let first = self.amplitude * angle.cos() as f32;
+        let second = self.amplitude * angle.sin() as f32;
+        let (x, y, z) = match self.plane {
+            AngleEn::Yz => (0.0, first, second),
+            AngleEn::Zx => (second, 0.0, first),
+            _ => (first, second, 0.0),
+        };
+        Sample::new(x, y, z, self.temperature)
+    }
+}
+
+/// Axially magnetized magnet moving back and forth along X above the sensor, modelled as a dipole
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LinearStroke {
+    peak: f32,
+    air_gap: f32,
+    stroke: f32,
+    frequency: f64,
+    temperature: f32,
+}
+
+impl LinearStroke {
+    /// Creates stroke of `stroke` mm at `frequency` Hz over an air gap of `air_gap` mm.
+    /// `peak` is the field in mT with the magnet right above the sensor
+    pub fn new(peak: f32, air_gap: f32, stroke: f32, frequency: f64) -> Self {
+        LinearStroke {
+            peak,
+            air_gap,
+            stroke,
+            frequency,
+            temperature: 25.0,
+        }
+    }
+
+    /// Set temperature in °C
+    pub fn set_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Magnet position along X in mm
+    pub fn position(&self, t: f64) -> f32 {
+        self.stroke / 2.0 * (2.0 * PI * self.frequency * t).sin() as f32
+    }
+}
+
+impl Scenario for LinearStroke {
+    fn sample(&mut self, t: f64) -> Sample {
+        let s = self.position(t);
+        let d = self.air_gap;
+        let r2 = s * s + d * d;
+        let r = r2.sqrt();
+        let scale = self.peak * d * d * d / (r2 * r);
+        let x = scale * (3.0 * s * s / r2 - 1.0);
+        let z = scale * 3.0 * s * d / r2;
+        Sample::new(x, 0.0, z, self.temperature)
+    }
+}
+
+/// Field of a straight conductor carrying a sinusoidal current
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SinusoidalCurrent {
+    peak: f32,
+    offset: f32,
+    frequency: f64,
+    distance: f32,
+    axis: Axis,
+    temperature: f32,
+}
+
+impl SinusoidalCurrent {
+    /// Creates current of `peak` A at `frequency` Hz in a conductor `distance` mm away,
+    /// the field is along Y
+    pub fn new(peak: f32, frequency: f64, distance: f32) -> Self {
+        SinusoidalCurrent {
+            peak,
+            offset: 0.0,
+            frequency,
+            distance,
+            axis: Axis::Y,
+            temperature: 25.0,
+        }
+    }
+
+    /// Set DC current in A added to the sine
+    pub fn set_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Set axis of the field
+    pub fn set_axis(mut self, axis: Axis) -> Self {
+        self.axis = axis;
+        self
+    }
+
+    /// Set temperature in °C
+    pub fn set_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Current in A
+    pub fn current(&self, t: f64) -> f32 {
+        self.offset + self.peak * (2.0 * PI * self.frequency * t).sin() as f32
+    }
+}
+
+impl Scenario for SinusoidalCurrent {
+    fn sample(&mut self, t: f64) -> Sample {
+        // B = µ0 I / (2 π d), 0.2 mT per A at 1 mm
+        let field = 0.2 * self.current(t) / self.distance;
+        let (x, y, z) = match self.axis {
+            Axis::X => (field, 0.0, 0.0),
+            Axis::Y => (0.0, field, 0.0),
+            Axis::Z => (0.0, 0.0, field),
+        };
+        Sample::new(x, y, z, self.temperature)
+    }
+}
+
+/// Jump from one sample to another
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Step {
+    from: Sample,
+    to: Sample,
+    at: f64,
+}
+
+impl Step {
+    /// Creates step from `from` to `to` at `at` seconds
+    pub fn new(from: Sample, to: Sample, at: f64) -> Self {
+        Step { from, to, at }
+    }
+}
+
+impl Scenario for Step {
+    fn sample(&mut self, t: f64) -> Sample {
+        if t < self.at {
+            self.from
+        } else {
+            self.to
+        }
+    }
+}
+
+/// Linear transition from one sample to another
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ramp {
+    from: Sample,
+    to: Sample,
+    start: f64,
+    end: f64,
+}
+
+impl Ramp {
+    /// Creates ramp from `from` at `start` seconds to `to` at `end` seconds
+    pub fn new(from: Sample, to: Sample, start: f64, end: f64) -> Self {
+        Ramp {
+            from,
+            to,
+            start,
+            end,
+        }
+    }
+}
+
+impl Scenario for Ramp {
+    fn sample(&mut self, t: f64) -> Sample {
+        if t <= self.start {
+            self.from
+        } else if t >= self.end {
+            self.to
+        } else {
+            let ratio = (t - self.start) / (self.end - self.start);
+            self.from.lerp(self.to, ratio as f32)
+        }
+    }
+}
+
+/// Uniform stray field added to another scenario
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StrayField<S> {
+    inner: S,
+    x: f32,
+    y: f32,
+    z: f32,
+    frequency: f64,
+}
+
+impl<S: Scenario> StrayField<S> {
+    /// Adds a constant field of (x,y,z) mT to `inner`
+    pub fn new(inner: S, x: f32, y: f32, z: f32) -> Self {
+        StrayField {
+            inner,
+            x,
+            y,
+            z,
+            frequency: 0.0,
+        }
+    }
+
+    /// Set frequency in Hz, (x,y,z) becomes the peak of a sinusoidal field. 0 is a constant field
+    pub fn set_frequency(mut self, frequency: f64) -> Self {
+        self.frequency = frequency;
+        self
+    }
+}
+
+impl<S: Scenario> Scenario for StrayField<S> {
+    fn sample(&mut self, t: f64) -> Sample {
+        let gain = if self.frequency == 0.0 {
+            1.0
+        } else {
+            (2.0 * PI * self.frequency * t).sin() as f32
+        };
+        let mut sample = self.inner.sample(t);
+        sample.x += self.x * gain;
+        sample.y += self.y * gain;
+        sample.z += self.z * gain;
+        sample
+    }
+}
+
+/// Gaussian noise added to another scenario, reproducible from its seed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Noise<S> {
+    inner: S,
+    sigma: f32,
+    temperature_sigma: f32,
+    state: u32,
+    spare: Option<f32>,
+}
+
+impl<S: Scenario> Noise<S> {
+    /// Adds noise with a standard deviation of `sigma` mT to every field component
+    pub fn new(inner: S, sigma: f32, seed: u32) -> Self {
+        Noise {
+            inner,
+            sigma,
+            temperature_sigma: 0.0,
+            state: if seed == 0 { 0x2545_f491 } else { seed },
+            spare: None,
+        }
+    }
+
+    /// Set standard deviation of the temperature noise in °C
+    pub fn set_temperature_sigma(mut self, sigma: f32) -> Self {
+        self.temperature_sigma = sigma;
+        self
+    }
+
+    fn uniform(&mut self) -> f32 {
+        // xorshift32
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        // Open interval (0, 1] keeps ln() finite
+        ((self.state >> 8) as f32 + 1.0) / (1u32 << 24) as f32
+    }
+
+    fn gaussian(&mut self) -> f32 {
+        if let Some(spare) = self.spare.take() {
+            return spare;
+        }
+
+        // Box-Muller
+        let r = (-2.0 * self.uniform().ln()).sqrt();
+        let theta = 2.0 * core::f32::consts::PI * self.uniform();
+        self.spare = Some(r * theta.sin());
+        r * theta.cos()
+    }
+}
+
+impl<S: Scenario> Scenario for Noise<S> {
+    fn sample(&mut self, t: f64) -> Sample {
+        let mut sample = self.inner.sample(t);
+        sample.x += self.sigma * self.gaussian();
+        sample.y += self.sigma * self.gaussian();
+        sample.z += self.sigma * self.gaussian();
+        sample.temperature += self.temperature_sigma * self.gaussian();
+        sample
+    }
+}
+
+/// Register codes of one conversion
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Codes {
+    /// X_CH_RESULT
+    pub x: i16,
+
+    /// Y_CH_RESULT
+    pub y: i16,
+
+    /// Z_CH_RESULT
+    pub z: i16,
+
+    /// TEMP_RESULT
+    pub t: u16,
+}
+
+/// Magnetic result code of a field in mT for a full scale in mT
+pub fn mag_code(field: f32, full_scale: f32) -> i16 {
+    (field / full_scale * 32768.0)
+        .round()
+        .clamp(-32768.0, 32767.0) as i16
+}
+
+/// Temperature result code of a temperature in °C
+pub fn temp_code(temperature: f32) -> u16 {
+    crate::temp_code(temperature)
+}
+
+/// Converts scenarios to register codes with the Range and ConvAvg of a configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Converter {
+    version: Version,
+    sensor: SensorConfig,
+    device: DeviceConfig,
+}
+
+impl Converter {
+    /// Creates converter for the device version and configuration
+    pub fn new(version: Version, sensor: SensorConfig, device: DeviceConfig) -> Self {
+        Converter {
+            version,
+            sensor,
+            device,
+        }
+    }
+
+    /// Number of averaged samples, a reserved ConvAvg code is taken as 32x
+    pub fn averaging(&self) -> u32 {
+        match self.device.conv_avg() {
+            Some(ConvAvg::Avg1x) => 1,
+            Some(ConvAvg::Avg2x) => 2,
+            Some(ConvAvg::Avg4x) => 4,
+            Some(ConvAvg::Avg8x) => 8,
+            Some(ConvAvg::Avg16x) => 16,
+            Some(ConvAvg::Avg32x) | None => 32,
+        }
+    }
+
+    /// Duration of one conversion of the enabled channels in seconds
+    pub fn conversion_time(&self) -> f64 {
+        SAMPLE_PERIOD * self.channels() as f64 * self.averaging() as f64
+    }
+
+    fn channels(&self) -> u32 {
+        let mag_ch_en = self.sensor.mag_ch_en();
+        let enabled = [
+            mag_ch_en.enables_x(),
+            mag_ch_en.enables_y(),
+            mag_ch_en.enables_z(),
+            self.device.t_en(),
+        ];
+        (enabled.iter().filter(|&&e| e).count() as u32).max(1)
+    }
+
+    fn full_scale(&self, range: Option<Range>) -> f32 {
+        range
+            .unwrap_or(Range::A1_50mT_A2_200mT)
+            .full_scale(self.version)
+    }
+
+    /// Codes of one sample without averaging
+    pub fn codes(&self, sample: Sample) -> Codes {
+        Codes {
+            x: mag_code(sample.x, self.full_scale(self.sensor.x_range())),
+            y: mag_code(sample.y, self.full_scale(self.sensor.y_range())),
+            z: mag_code(sample.z, self.full_scale(self.sensor.z_range())),
+            t: temp_code(sample.temperature),
+        }
+    }
+
+    /// Averages the scenario over the conversion starting at `t` seconds, like the device does
+    pub fn convert<S: Scenario>(&self, scenario: &mut S, t: f64) -> Codes {
+        let averaging = self.averaging();
+        let step = SAMPLE_PERIOD * self.channels() as f64;
+        let mut sum = Sample::new(0.0, 0.0, 0.0, 0.0);
+        for i in 0..averaging {
+            let sample = scenario.sample(t + step * i as f64);
+            sum.x += sample.x;
+            sum.y += sample.y;
+            sum.z += sample.z;
+            sum.temperature += sample.temperature;
+        }
+
+        let n = averaging as f32;
+        self.codes(Sample::new(
+            sum.x / n,
+            sum.y / n,
+            sum.z / n,
+            sum.temperature / n,
+        ))
+    }
+}
@@ -0,0 +1,75 @@
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::diagnostics::DiagnosticReport;
+use crate::retry::Backoff;
+use crate::status::{AfeStatus, SysStatus};
+use crate::system_config::DiagSel;
+use crate::test_config::{TestConfig, Version};
+use crate::{ExtError, Tmag5170};
+
+/// Outcome of the startup self-test
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SelfTestReport {
+    /// TEST_CONFIG read over a CRC checked frame
+    pub test_config: TestConfig,
+
+    /// AFE_STATUS read before the diagnostic run
+    pub afe: AfeStatus,
+
+    /// SYS_STATUS read before the diagnostic run
+    pub sys: SysStatus,
+
+    /// Result of the diagnostic run
+    pub diagnostics: DiagnosticReport,
+}
+
+impl SelfTestReport {
+    /// Device version, `None` if the version bits hold an unknown code
+    pub fn version(&self) -> Option<Version> {
+        self.test_config.ver()
+    }
+
+    /// Returns true if the version is known, no status error is pending
+    /// and the diagnostic run passed
+    pub fn is_ok(&self) -> bool {
+        let status = DiagnosticReport::new(self.diagnostics.diag_sel(), self.afe, self.sys);
+        self.version().is_some() && status.is_ok() && self.diagnostics.is_ok()
+    }
+}
+
+impl<SPI, CS, B, E, PE> Tmag5170<SPI, CS, B>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    B: Backoff,
+{
+    /// Reads TMAG5170-Q1 TEST_CONFIG register.
+    pub fn read_test_config(&mut self) -> Result<TestConfig, ExtError<E, PE>> {
+        let tc = self.read_test_config_register()?;
+
+        Ok(TestConfig::form_u16(tc as u16))
+    }
+
+    /// Startup self-test: checks communication and version, reads the status registers and runs
+    /// all data path diagnostics. A pending CFG_RESET is cleared by the AFE_STATUS read
+    pub fn self_test<D: DelayUs<u32>>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<SelfTestReport, ExtError<E, PE>> {
+        let test_config = self.read_test_config()?;
+        let afe = self.read_afe_status()?;
+        let sys = self.read_sys_status()?;
+        let diagnostics = self.run_diagnostics(DiagSel::AllData, delay, timeout_us)?;
+
+        Ok(SelfTestReport {
+            test_config,
+            afe,
+            sys,
+            diagnostics,
+        })
+    }
+}
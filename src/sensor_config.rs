@@ -1,4 +1,9 @@
+use crate::test_config::Version;
+
 /// Enable Angle calculation using two axis data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum AngleEn {
     /// No angle calculation (default)
     NoAngle = 0x00,
@@ -13,8 +18,22 @@ pub enum AngleEn {
     Zx = 0x03,
 }
 
+impl AngleEn {
+    pub(crate) fn from_bits(bits: u16) -> Self {
+        match bits {
+            0x00 => AngleEn::NoAngle,
+            0x01 => AngleEn::Xy,
+            0x02 => AngleEn::Yz,
+            _ => AngleEn::Zx,
+        }
+    }
+}
+
 /// Selects the time spent in low power mode between conversions
 /// when OPERATING_MODE =010b (OperatingMode::Active)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SleepTime {
     /// 1ms
     Ms1 = 0x00,
@@ -47,8 +66,29 @@ pub enum SleepTime {
     Ms1000 = 0x09,
 }
 
+impl SleepTime {
+    pub(crate) fn from_bits(bits: u16) -> Option<Self> {
+        match bits {
+            0x00 => Some(SleepTime::Ms1),
+            0x01 => Some(SleepTime::Ms5),
+            0x02 => Some(SleepTime::Ms10),
+            0x03 => Some(SleepTime::Ms15),
+            0x04 => Some(SleepTime::Ms20),
+            0x05 => Some(SleepTime::Ms30),
+            0x06 => Some(SleepTime::Ms50),
+            0x07 => Some(SleepTime::Ms100),
+            0x08 => Some(SleepTime::Ms500),
+            0x09 => Some(SleepTime::Ms1000),
+            _ => None,
+        }
+    }
+}
+
 /// Enables data acquisition of the magnetic axis channel(s)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum MagChEn {
     /// All magnetic channels of OFF
     Off = 0x00,
@@ -99,8 +139,85 @@ pub enum MagChEn {
     Xyzzyx = 0x0f,
 }
 
+impl MagChEn {
+    pub(crate) fn from_bits(bits: u16) -> Self {
+        match bits {
+            0x00 => MagChEn::Off,
+            0x01 => MagChEn::X,
+            0x02 => MagChEn::Y,
+            0x03 => MagChEn::Xy,
+            0x04 => MagChEn::Z,
+            0x05 => MagChEn::Zx,
+            0x06 => MagChEn::Yz,
+            0x07 => MagChEn::Xyz,
+            0x08 => MagChEn::Xyx,
+            0x09 => MagChEn::Yxy,
+            0x0a => MagChEn::Yzy,
+            0x0b => MagChEn::Zyz,
+            0x0c => MagChEn::Zxz,
+            0x0d => MagChEn::Xzx,
+            0x0e => MagChEn::Xyzyx,
+            _ => MagChEn::Xyzzyx,
+        }
+    }
+
+    /// Is X channel part of the conversion sequence
+    pub fn enables_x(self) -> bool {
+        matches!(
+            self,
+            MagChEn::X
+                | MagChEn::Xy
+                | MagChEn::Zx
+                | MagChEn::Xyz
+                | MagChEn::Xyx
+                | MagChEn::Yxy
+                | MagChEn::Zxz
+                | MagChEn::Xzx
+                | MagChEn::Xyzyx
+                | MagChEn::Xyzzyx
+        )
+    }
+
+    /// Is Y channel part of the conversion sequence
+    pub fn enables_y(self) -> bool {
+        matches!(
+            self,
+            MagChEn::Y
+                | MagChEn::Xy
+                | MagChEn::Yz
+                | MagChEn::Xyz
+                | MagChEn::Xyx
+                | MagChEn::Yxy
+                | MagChEn::Yzy
+                | MagChEn::Zyz
+                | MagChEn::Xyzyx
+                | MagChEn::Xyzzyx
+        )
+    }
+
+    /// Is Z channel part of the conversion sequence
+    pub fn enables_z(self) -> bool {
+        matches!(
+            self,
+            MagChEn::Z
+                | MagChEn::Zx
+                | MagChEn::Yz
+                | MagChEn::Xyz
+                | MagChEn::Yzy
+                | MagChEn::Zyz
+                | MagChEn::Zxz
+                | MagChEn::Xzx
+                | MagChEn::Xyzyx
+                | MagChEn::Xyzzyx
+        )
+    }
+}
+
 /// Enables different magnetic ranges
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Range {
     /// ±50mT (TMAG5170A1) / ±200mT(TMAG5170A2)
     A1_50mT_A2_200mT = 0x00,
@@ -112,12 +229,39 @@ pub enum Range {
     A1_100mT_A2_300mT = 0x02,
 }
 
+impl Range {
+    pub(crate) fn from_bits(bits: u16) -> Option<Self> {
+        match bits {
+            0x00 => Some(Range::A1_50mT_A2_200mT),
+            0x01 => Some(Range::A1_25mT_A2_133mT),
+            0x02 => Some(Range::A1_100mT_A2_300mT),
+            _ => None,
+        }
+    }
+
+    /// Full scale field in mT of the range on the device version
+    pub fn full_scale(self, version: Version) -> f32 {
+        match (version, self) {
+            (Version::A1, Range::A1_50mT_A2_200mT) => 50.0,
+            (Version::A1, Range::A1_25mT_A2_133mT) => 25.0,
+            (Version::A1, Range::A1_100mT_A2_300mT) => 100.0,
+            (Version::A2, Range::A1_50mT_A2_200mT) => 200.0,
+            (Version::A2, Range::A1_25mT_A2_133mT) => 133.0,
+            (Version::A2, Range::A1_100mT_A2_300mT) => 300.0,
+        }
+    }
+}
+
 /// Configure Device Operation Modes - SENSOR_CONFIG
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SensorConfig {
     config: u16,
 }
 
 impl SensorConfig {
+    /// Reserved bits of SENSOR_CONFIG
+    pub const RESERVED_BITS: u16 = 0x0000;
+
     /// Creates default config
     pub fn new() -> Self {
         let config = 0x00;
@@ -169,6 +313,36 @@ impl SensorConfig {
         self.config = self.config & !(0b11) | (x_range as u16);
         self
     }
+
+    /// Get AngleEn field
+    pub fn angle_en(&self) -> AngleEn {
+        AngleEn::from_bits((self.config >> 14) & 0b11)
+    }
+
+    /// Get SleepTime field, `None` if it holds a reserved code
+    pub fn sleep_time(&self) -> Option<SleepTime> {
+        SleepTime::from_bits((self.config >> 10) & 0b1111)
+    }
+
+    /// Get MagChEn field
+    pub fn mag_ch_en(&self) -> MagChEn {
+        MagChEn::from_bits((self.config >> 6) & 0b1111)
+    }
+
+    /// Get Z Range field, `None` if it holds a reserved code
+    pub fn z_range(&self) -> Option<Range> {
+        Range::from_bits((self.config >> 4) & 0b11)
+    }
+
+    /// Get Y Range field, `None` if it holds a reserved code
+    pub fn y_range(&self) -> Option<Range> {
+        Range::from_bits((self.config >> 2) & 0b11)
+    }
+
+    /// Get X Range field, `None` if it holds a reserved code
+    pub fn x_range(&self) -> Option<Range> {
+        Range::from_bits(self.config & 0b11)
+    }
 }
 
 impl Default for SensorConfig {
@@ -0,0 +1,417 @@
+//! Named-field serde representations of the register types. Configs and status registers
+//! are serialized through these structs instead of their raw u16 value
+
+use core::fmt;
+
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::alert_config::{AlertConfig, AlertLatch, AlertMode, ThrxCount};
+use crate::device_config::{ConvAvg, DeviceConfig, MagTempco, OperatingMode, TRate};
+use crate::frame::FrameStatus;
+use crate::sensor_config::{AngleEn, MagChEn, Range, SensorConfig, SleepTime};
+use crate::status::{AfeStatus, ConvStatus, SysStatus};
+use crate::system_config::{DataType, DiagSel, SystemConfig, TriggerMode};
+use crate::test_config::{OscCntCtl, TestConfig, Version};
+
+/// A config field holds a reserved code which has no enum variant
+struct Reserved(&'static str);
+
+impl fmt::Display for Reserved {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} holds a reserved code", self.0)
+    }
+}
+
+fn field<T, E: ser::Error>(value: Option<T>, name: &'static str) -> Result<T, E> {
+    value.ok_or_else(|| E::custom(Reserved(name)))
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "DeviceConfig")]
+struct DeviceConfigFields {
+    conv_avg: ConvAvg,
+    mag_tempco: MagTempco,
+    operating_mode: OperatingMode,
+    t_en: bool,
+    t_rate: TRate,
+    t_limit_check_en: bool,
+    t_comp_en: bool,
+}
+
+impl Serialize for DeviceConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DeviceConfigFields {
+            conv_avg: field(self.conv_avg(), "CONV_AVG")?,
+            mag_tempco: field(self.mag_tempco(), "MAG_TEMPCO")?,
+            operating_mode: field(self.operating_mode(), "OPERATING_MODE")?,
+            t_en: self.t_en(),
+            t_rate: self.t_rate(),
+            t_limit_check_en: self.t_limit_check_en(),
+            t_comp_en: self.t_comp_en(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DeviceConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let f = DeviceConfigFields::deserialize(deserializer)?;
+        Ok(DeviceConfig::new()
+            .set_conv_avg(f.conv_avg)
+            .set_mag_tempco(f.mag_tempco)
+            .set_operating_mode(f.operating_mode)
+            .set_t_en(f.t_en)
+            .set_t_rate(f.t_rate)
+            .set_t_limit_check_en(f.t_limit_check_en)
+            .set_t_comp_en(f.t_comp_en))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "SensorConfig")]
+struct SensorConfigFields {
+    angle_en: AngleEn,
+    sleep_time: SleepTime,
+    mag_ch_en: MagChEn,
+    z_range: Range,
+    y_range: Range,
+    x_range: Range,
+}
+
+impl Serialize for SensorConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SensorConfigFields {
+            angle_en: self.angle_en(),
+            sleep_time: field(self.sleep_time(), "SLEEPTIME")?,
+            mag_ch_en: self.mag_ch_en(),
+            z_range: field(self.z_range(), "Z_RANGE")?,
+            y_range: field(self.y_range(), "Y_RANGE")?,
+            x_range: field(self.x_range(), "X_RANGE")?,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SensorConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let f = SensorConfigFields::deserialize(deserializer)?;
+        Ok(SensorConfig::new()
+            .set_angle_en(f.angle_en)
+            .set_sleep_time(f.sleep_time)
+            .set_mag_ch_en(f.mag_ch_en)
+            .set_z_range(f.z_range)
+            .set_y_range(f.y_range)
+            .set_x_range(f.x_range))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "SystemConfig")]
+struct SystemConfigFields {
+    diag_sel: DiagSel,
+    trigger_mode: TriggerMode,
+    data_type: DataType,
+    diag_en: bool,
+    t_z_limit_check: bool,
+    t_y_limit_check: bool,
+    t_x_limit_check: bool,
+}
+
+impl Serialize for SystemConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SystemConfigFields {
+            diag_sel: self.diag_sel(),
+            trigger_mode: field(self.trigger_mode(), "TRIGGER_MODE")?,
+            data_type: self.data_type(),
+            diag_en: self.diag_en(),
+            t_z_limit_check: self.t_z_limit_check(),
+            t_y_limit_check: self.t_y_limit_check(),
+            t_x_limit_check: self.t_x_limit_check(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SystemConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let f = SystemConfigFields::deserialize(deserializer)?;
+        Ok(SystemConfig::new()
+            .set_diag_sel(f.diag_sel)
+            .set_trigger_mode(f.trigger_mode)
+            .set_data_type(f.data_type)
+            .set_diag_en(f.diag_en)
+            .set_t_z_limit_check(f.t_z_limit_check)
+            .set_t_y_limit_check(f.t_y_limit_check)
+            .set_t_x_limit_check(f.t_x_limit_check))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "AlertConfig")]
+struct AlertConfigFields {
+    alert_latch: AlertLatch,
+    alert_mode: AlertMode,
+    status_alrt_enable: bool,
+    rslt_alrt_enable: bool,
+    thrx_count: ThrxCount,
+    t_thrx_alrt_enable: bool,
+    z_thrx_alrt_enable: bool,
+    y_thrx_alrt_enable: bool,
+    x_thrx_alrt_enable: bool,
+}
+
+impl Serialize for AlertConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        AlertConfigFields {
+            alert_latch: self.alert_latch(),
+            alert_mode: self.alert_mode(),
+            status_alrt_enable: self.status_alrt_enable(),
+            rslt_alrt_enable: self.rslt_alrt_enable(),
+            thrx_count: self.thrx_count(),
+            t_thrx_alrt_enable: self.t_thrx_alrt_enable(),
+            z_thrx_alrt_enable: self.z_thrx_alrt_enable(),
+            y_thrx_alrt_enable: self.y_thrx_alrt_enable(),
+            x_thrx_alrt_enable: self.x_thrx_alrt_enable(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AlertConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let f = AlertConfigFields::deserialize(deserializer)?;
+        Ok(AlertConfig::new()
+            .set_alert_latch(f.alert_latch)
+            .set_alert_mode(f.alert_mode)
+            .set_status_alrt_enable(f.status_alrt_enable)
+            .set_rslt_alrt_enable(f.rslt_alrt_enable)
+            .set_thrx_count(f.thrx_count)
+            .set_t_thrx_alrt_enable(f.t_thrx_alrt_enable)
+            .set_z_thrx_alrt_enable(f.z_thrx_alrt_enable)
+            .set_y_thrx_alrt_enable(f.y_thrx_alrt_enable)
+            .set_x_thrx_alrt_enable(f.x_thrx_alrt_enable))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "TestConfig")]
+struct TestConfigFields {
+    ver: Option<Version>,
+    crc_dis: bool,
+    osc_cnt_ctl: OscCntCtl,
+}
+
+impl Serialize for TestConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TestConfigFields {
+            ver: self.ver(),
+            crc_dis: self.crc_dis(),
+            osc_cnt_ctl: self.osc_cnt_ctl(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TestConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let f = TestConfigFields::deserialize(deserializer)?;
+        Ok(TestConfig::new()
+            .set_ver(f.ver)
+            .set_crc_dis(f.crc_dis)
+            .set_osc_cnt_ctl(f.osc_cnt_ctl))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "ConvStatus")]
+struct ConvStatusFields {
+    rdy: bool,
+    a: bool,
+    t: bool,
+    z: bool,
+    y: bool,
+    x: bool,
+    set_count: u8,
+    alrt_status: u8,
+}
+
+impl Serialize for ConvStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ConvStatusFields {
+            rdy: self.rdy(),
+            a: self.a(),
+            t: self.t(),
+            z: self.z(),
+            y: self.y(),
+            x: self.x(),
+            set_count: self.set_count(),
+            alrt_status: self.alrt_status(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ConvStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let f = ConvStatusFields::deserialize(deserializer)?;
+        if f.set_count > 0b111 || f.alrt_status > 0b11 {
+            return Err(de::Error::custom("CONV_STATUS counter out of range"));
+        }
+        Ok(ConvStatus::from_u16(0)
+            .set_rdy(f.rdy)
+            .set_a(f.a)
+            .set_t(f.t)
+            .set_z(f.z)
+            .set_y(f.y)
+            .set_x(f.x)
+            .set_set_count(f.set_count)
+            .set_alrt_status(f.alrt_status))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "AfeStatus")]
+struct AfeStatusFields {
+    cfg_reset: bool,
+    sens_stat: bool,
+    temp_stat: bool,
+    zhs_stat: bool,
+    yhs_stat: bool,
+    xhs_stat: bool,
+    trim_stat: bool,
+    ldo_stat: bool,
+}
+
+impl Serialize for AfeStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        AfeStatusFields {
+            cfg_reset: self.cfg_reset(),
+            sens_stat: self.sens_stat(),
+            temp_stat: self.temp_stat(),
+            zhs_stat: self.zhs_stat(),
+            yhs_stat: self.yhs_stat(),
+            xhs_stat: self.xhs_stat(),
+            trim_stat: self.trim_stat(),
+            ldo_stat: self.ldo_stat(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AfeStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let f = AfeStatusFields::deserialize(deserializer)?;
+        Ok(AfeStatus::from_u16(0)
+            .set_cfg_reset(f.cfg_reset)
+            .set_sens_stat(f.sens_stat)
+            .set_temp_stat(f.temp_stat)
+            .set_zhs_stat(f.zhs_stat)
+            .set_yhs_stat(f.yhs_stat)
+            .set_xhs_stat(f.xhs_stat)
+            .set_trim_stat(f.trim_stat)
+            .set_ldo_stat(f.ldo_stat))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "SysStatus")]
+struct SysStatusFields {
+    alrt_lvl: bool,
+    alrt_drv: bool,
+    sdo_drv: bool,
+    crc_stat: bool,
+    frame_stat: bool,
+    operating_stat: u8,
+    vcc_ov: bool,
+    vcc_uv: bool,
+    temp_thx: bool,
+    zch_thx: bool,
+    ych_thx: bool,
+    xch_thx: bool,
+}
+
+impl Serialize for SysStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SysStatusFields {
+            alrt_lvl: self.alrt_lvl(),
+            alrt_drv: self.alrt_drv(),
+            sdo_drv: self.sdo_drv(),
+            crc_stat: self.crc_stat(),
+            frame_stat: self.frame_stat(),
+            operating_stat: self.operating_stat(),
+            vcc_ov: self.vcc_ov(),
+            vcc_uv: self.vcc_uv(),
+            temp_thx: self.temp_thx(),
+            zch_thx: self.zch_thx(),
+            ych_thx: self.ych_thx(),
+            xch_thx: self.xch_thx(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SysStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let f = SysStatusFields::deserialize(deserializer)?;
+        if f.operating_stat > 0b111 {
+            return Err(de::Error::custom("SYS_STATUS operating_stat out of range"));
+        }
+        Ok(SysStatus::from_u16(0)
+            .set_alrt_lvl(f.alrt_lvl)
+            .set_alrt_drv(f.alrt_drv)
+            .set_sdo_drv(f.sdo_drv)
+            .set_crc_stat(f.crc_stat)
+            .set_frame_stat(f.frame_stat)
+            .set_operating_stat(f.operating_stat)
+            .set_vcc_ov(f.vcc_ov)
+            .set_vcc_uv(f.vcc_uv)
+            .set_temp_thx(f.temp_thx)
+            .set_zch_thx(f.zch_thx)
+            .set_ych_thx(f.ych_thx)
+            .set_xch_thx(f.xch_thx))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "FrameStatus")]
+struct FrameStatusFields {
+    prev_crc_error: bool,
+    cfg_reset: bool,
+    sys_alert: bool,
+    afe_alert: bool,
+    x_stat: bool,
+    y_stat: bool,
+    z_stat: bool,
+    t_stat: bool,
+}
+
+impl Serialize for FrameStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        FrameStatusFields {
+            prev_crc_error: self.prev_crc_error(),
+            cfg_reset: self.cfg_reset(),
+            sys_alert: self.sys_alert(),
+            afe_alert: self.afe_alert(),
+            x_stat: self.x_stat(),
+            y_stat: self.y_stat(),
+            z_stat: self.z_stat(),
+            t_stat: self.t_stat(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FrameStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let f = FrameStatusFields::deserialize(deserializer)?;
+        Ok(FrameStatus::from_u16(0)
+            .set_prev_crc_error(f.prev_crc_error)
+            .set_cfg_reset(f.cfg_reset)
+            .set_sys_alert(f.sys_alert)
+            .set_afe_alert(f.afe_alert)
+            .set_x_stat(f.x_stat)
+            .set_y_stat(f.y_stat)
+            .set_z_stat(f.z_stat)
+            .set_t_stat(f.t_stat))
+    }
+}
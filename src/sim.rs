@@ -0,0 +1,481 @@
+use core::cell::RefCell;
+use core::convert::Infallible;
+use std::rc::Rc;
+
+use crc_all::Crc;
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::device_config::{DeviceConfig, OperatingMode};
+use crate::frame::{self, FrameStatus};
+use crate::scenario::{Converter, Sample};
+use crate::sensor_config::{AngleEn, SensorConfig};
+use crate::system_config::{DataType, SystemConfig, TriggerMode};
+use crate::test_config::{TestConfig, Version};
+use crate::Register;
+
+const REGISTER_COUNT: usize = 0x15;
+
+/// Behavioural model of a TMAG5170-Q1 driven through its [`SimSpi`] and [`SimCs`] handles.
+/// Clones share the same device
+#[derive(Clone)]
+pub struct SimDevice {
+    state: Rc<RefCell<State>>,
+}
+
+impl SimDevice {
+    /// Creates powered device with registers at their reset values and no field
+    pub fn new(version: Version) -> Self {
+        let mut state = State {
+            crc: Crc::<u8>::new(0x03, 4, 0x0f, 0x00, false),
+            version,
+            registers: [0; REGISTER_COUNT],
+            field: (0.0, 0.0, 0.0),
+            temperature: 25.0,
+            powered: true,
+            cs_low: false,
+            clocked: false,
+            waking: false,
+            conv_pending: false,
+            prev_crc_error: false,
+            corrupt_requests: 0,
+            corrupt_responses: 0,
+            frames: 0,
+        };
+        state.power_on_reset();
+
+        SimDevice {
+            state: Rc::new(RefCell::new(state)),
+        }
+    }
+
+    /// SPI bus of the device
+    pub fn spi(&self) -> SimSpi {
+        SimSpi {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Chip select pin of the device
+    pub fn cs(&self) -> SimCs {
+        SimCs {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Sets the magnetic field in mT seen by the X, Y and Z Hall elements
+    pub fn set_field(&self, x: f32, y: f32, z: f32) {
+        self.state.borrow_mut().field = (x, y, z);
+    }
+
+    /// Sets the die temperature in °C
+    pub fn set_temperature(&self, celsius: f32) {
+        self.state.borrow_mut().temperature = celsius;
+    }
+
+    /// Sets field and temperature from a scenario sample
+    pub fn set_sample(&self, sample: Sample) {
+        let mut state = self.state.borrow_mut();
+        state.field = (sample.x, sample.y, sample.z);
+        state.temperature = sample.temperature;
+    }
+
+    /// Runs one conversion of the enabled channels immediately
+    pub fn convert(&self) {
+        self.state.borrow_mut().convert();
+    }
+
+    /// Current register value
+    pub fn register(&self, reg: Register) -> u16 {
+        self.state.borrow().registers[reg.addr() as usize]
+    }
+
+    /// Overwrites a register, including read-only bits, e.g. to inject status errors
+    pub fn set_register(&self, reg: Register, value: u16) {
+        self.state.borrow_mut().registers[reg.addr() as usize] = value;
+    }
+
+    /// Powers the device off or on. An unpowered device drives SDO low,
+    /// powering it on resets the registers
+    pub fn set_powered(&self, powered: bool) {
+        let mut state = self.state.borrow_mut();
+        if powered && !state.powered {
+            state.power_on_reset();
+        }
+        state.powered = powered;
+    }
+
+    /// Resets the registers to their default values and sets CFG_RESET
+    pub fn power_on_reset(&self) {
+        self.state.borrow_mut().power_on_reset();
+    }
+
+    /// Flips a CRC bit of the next `count` SDI frames, the device ignores them
+    pub fn corrupt_requests(&self, count: u32) {
+        self.state.borrow_mut().corrupt_requests = count;
+    }
+
+    /// Flips a CRC bit of the next `count` SDO frames
+    pub fn corrupt_responses(&self, count: u32) {
+        self.state.borrow_mut().corrupt_responses = count;
+    }
+
+    /// Number of SPI frames clocked since creation
+    pub fn frames(&self) -> u32 {
+        self.state.borrow().frames
+    }
+}
+
+/// SPI bus of a [`SimDevice`]
+pub struct SimSpi {
+    state: Rc<RefCell<State>>,
+}
+
+impl Transfer<u8> for SimSpi {
+    type Error = Infallible;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Infallible> {
+        self.state.borrow_mut().transfer(words);
+        Ok(words)
+    }
+}
+
+/// Chip select pin of a [`SimDevice`]
+pub struct SimCs {
+    state: Rc<RefCell<State>>,
+}
+
+impl OutputPin for SimCs {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.state.borrow_mut().cs_falling();
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.state.borrow_mut().cs_rising();
+        Ok(())
+    }
+}
+
+struct State {
+    crc: Crc<u8>,
+    version: Version,
+    registers: [u16; REGISTER_COUNT],
+    field: (f32, f32, f32),
+    temperature: f32,
+    powered: bool,
+    cs_low: bool,
+    clocked: bool,
+    waking: bool,
+    conv_pending: bool,
+    prev_crc_error: bool,
+    corrupt_requests: u32,
+    corrupt_responses: u32,
+    frames: u32,
+}
+
+impl State {
+    fn reg(&self, reg: Register) -> u16 {
+        self.registers[reg.addr() as usize]
+    }
+
+    fn set_reg(&mut self, reg: Register, value: u16) {
+        self.registers[reg.addr() as usize] = value;
+    }
+
+    fn power_on_reset(&mut self) {
+        self.registers = [0; REGISTER_COUNT];
+        self.set_reg(Register::X_THRX_CONFIG, 0x7d83);
+        self.set_reg(Register::Y_THRX_CONFIG, 0x7d83);
+        self.set_reg(Register::Z_THRX_CONFIG, 0x7d83);
+        self.set_reg(Register::T_THRX_CONFIG, 0x6732);
+        self.set_reg(Register::AFE_STATUS, 0x8000);
+        self.set_reg(Register::TEST_CONFIG, (self.version as u16) << 4);
+        self.conv_pending = false;
+        self.prev_crc_error = false;
+    }
+
+    fn cs_falling(&mut self) {
+        self.cs_low = true;
+        self.clocked = false;
+
+        let device = DeviceConfig::form_u16(self.reg(Register::DEVICE_CONFIG));
+        // CS wakes the device up from deep sleep into configuration mode, it does not answer
+        // the frame
+        self.waking = self.powered && device.operating_mode() == Some(OperatingMode::DeepSleep);
+        if self.waking {
+            let config = self.reg(Register::DEVICE_CONFIG) & !(0b111 << 4);
+            self.set_reg(Register::DEVICE_CONFIG, config);
+            let sys = self.reg(Register::SYS_STATUS) & !(0b111 << 8);
+            self.set_reg(Register::SYS_STATUS, sys);
+            return;
+        }
+
+        let continuous = matches!(
+            device.operating_mode(),
+            Some(OperatingMode::Active) | Some(OperatingMode::WakeupAndSleep)
+        );
+        if self.powered && continuous {
+            self.convert();
+        }
+    }
+
+    fn cs_rising(&mut self) {
+        self.cs_low = false;
+        if !self.powered || self.waking {
+            self.waking = false;
+            return;
+        }
+
+        let system = SystemConfig::form_u16(self.reg(Register::SYSTEM_CONFIG));
+        let cs_trigger = !self.clocked && system.trigger_mode() == Some(TriggerMode::Cs);
+        if self.conv_pending || cs_trigger {
+            self.conv_pending = false;
+            self.convert();
+        }
+    }
+
+    fn transfer(&mut self, words: &mut [u8]) {
+        if !self.cs_low {
+            words.iter_mut().for_each(|w| *w = 0xff);
+            return;
+        }
+        if !self.powered || self.waking {
+            words.iter_mut().for_each(|w| *w = 0x00);
+            return;
+        }
+
+        self.clocked = true;
+        self.frames = self.frames.wrapping_add(1);
+        if words.len() != 4 {
+            let sys = self.reg(Register::SYS_STATUS) | (0b1 << 11);
+            self.set_reg(Register::SYS_STATUS, sys);
+            words.iter_mut().for_each(|w| *w = 0x00);
+            return;
+        }
+
+        let mut request = [words[0], words[1], words[2], words[3]];
+        if self.corrupt_requests > 0 {
+            self.corrupt_requests -= 1;
+            request[3] ^= 0x01;
+        }
+
+        let test_config = TestConfig::form_u16(self.reg(Register::TEST_CONFIG));
+        let crc_ok = test_config.crc_dis() || frame::crc_ok(&mut self.crc, &request);
+        let read = request[0] & 0x80 != 0;
+        let addr = request[0] & 0x7f;
+        let value = u16::from_be_bytes([request[1], request[2]]);
+        let cmd = request[3] >> 4;
+
+        let status = self.frame_status();
+        let system = SystemConfig::form_u16(self.reg(Register::SYSTEM_CONFIG));
+        let mut response = match system.data_type() {
+            DataType::Default => {
+                let out = match Register::from_addr(addr) {
+                    Some(reg) if read => self.reg(reg),
+                    _ => 0x0000,
+                };
+                frame::response(&mut self.crc, status, out)
+            }
+            data_type => {
+                let (ch1, ch2) = self.special_data(data_type);
+                frame::special_response(&mut self.crc, status, ch1, ch2)
+            }
+        };
+
+        self.prev_crc_error = !crc_ok;
+        if crc_ok {
+            if read {
+                self.clear_on_read(addr, system.data_type());
+            } else {
+                self.write(addr, value);
+            }
+            let spi_trigger = system.trigger_mode() == Some(TriggerMode::Spi);
+            if spi_trigger && cmd & 0b1 != 0 {
+                self.conv_pending = true;
+            }
+        } else {
+            let sys = self.reg(Register::SYS_STATUS) | (0b1 << 12);
+            self.set_reg(Register::SYS_STATUS, sys);
+        }
+
+        if self.corrupt_responses > 0 {
+            self.corrupt_responses -= 1;
+            response[3] ^= 0x01;
+        }
+        words.copy_from_slice(&response);
+    }
+
+    fn frame_status(&self) -> FrameStatus {
+        let afe = self.reg(Register::AFE_STATUS);
+        let sys = self.reg(Register::SYS_STATUS);
+        let conv = self.reg(Register::CONV_STATUS);
+
+        let mut status = 0;
+        if self.prev_crc_error {
+            status |= 0b1 << 11;
+        }
+        if afe & (0b1 << 15) != 0 {
+            status |= 0b1 << 10;
+        }
+        if sys & 0x783f != 0 {
+            status |= 0b1 << 9;
+        }
+        if afe & 0x1f03 != 0 {
+            status |= 0b1 << 8;
+        }
+        status |= ((conv >> 8) & 0b1) << 7;
+        status |= ((conv >> 9) & 0b1) << 6;
+        status |= ((conv >> 10) & 0b1) << 5;
+        status |= ((conv >> 11) & 0b1) << 4;
+
+        FrameStatus::from_u16(status)
+    }
+
+    fn write(&mut self, addr: u8, value: u16) {
+        let reg = match Register::from_addr(addr) {
+            Some(reg) => reg,
+            None => return,
+        };
+        let writable = reg.writable_bits();
+        let old = self.reg(reg);
+        self.set_reg(reg, old & !writable | value & writable);
+
+        if reg == Register::DEVICE_CONFIG {
+            let mode = (value >> 4) & 0b111;
+            let sys = self.reg(Register::SYS_STATUS) & !(0b111 << 8) | (mode << 8);
+            self.set_reg(Register::SYS_STATUS, sys);
+        }
+    }
+
+    fn clear_on_read(&mut self, addr: u8, data_type: DataType) {
+        if data_type != DataType::Default {
+            return;
+        }
+        match Register::from_addr(addr) {
+            Some(Register::AFE_STATUS) => {
+                let afe = self.reg(Register::AFE_STATUS) & !(0b1 << 15);
+                self.set_reg(Register::AFE_STATUS, afe);
+            }
+            Some(Register::SYS_STATUS) => {
+                let sys = self.reg(Register::SYS_STATUS) & !(0b11 << 11);
+                self.set_reg(Register::SYS_STATUS, sys);
+            }
+            _ => (),
+        }
+    }
+
+    fn special_data(&self, data_type: DataType) -> (u16, u16) {
+        let x = self.reg(Register::X_CH_RESULT) >> 4;
+        let y = self.reg(Register::Y_CH_RESULT) >> 4;
+        let z = self.reg(Register::Z_CH_RESULT) >> 4;
+        let t = self.reg(Register::TEMP_RESULT) >> 4;
+        match data_type {
+            DataType::XY => (x, y),
+            DataType::XZ => (x, z),
+            DataType::ZY => (z, y),
+            DataType::XT => (x, t),
+            DataType::YT => (y, t),
+            DataType::ZT => (z, t),
+            _ => (
+                self.reg(Register::ANGLE_RESULT) >> 1,
+                self.reg(Register::MAGNITUDE_RESULT),
+            ),
+        }
+    }
+
+    fn convert(&mut self) {
+        let device = DeviceConfig::form_u16(self.reg(Register::DEVICE_CONFIG));
+        let sensor = SensorConfig::form_u16(self.reg(Register::SENSOR_CONFIG));
+        let system = SystemConfig::form_u16(self.reg(Register::SYSTEM_CONFIG));
+        let mag_ch_en = sensor.mag_ch_en();
+        let (fx, fy, fz) = self.field;
+        let codes = Converter::new(self.version, sensor, device).codes(Sample::new(
+            fx,
+            fy,
+            fz,
+            self.temperature,
+        ));
+
+        let mut conv = self.reg(Register::CONV_STATUS) & (0b111 << 4);
+        if mag_ch_en.enables_x() {
+            self.set_reg(Register::X_CH_RESULT, codes.x as u16);
+            conv |= 0b1 << 8;
+        }
+        if mag_ch_en.enables_y() {
+            self.set_reg(Register::Y_CH_RESULT, codes.y as u16);
+            conv |= 0b1 << 9;
+        }
+        if mag_ch_en.enables_z() {
+            self.set_reg(Register::Z_CH_RESULT, codes.z as u16);
+            conv |= 0b1 << 10;
+        }
+        if device.t_en() {
+            self.set_reg(Register::TEMP_RESULT, codes.t);
+            conv |= 0b1 << 11;
+        }
+
+        let x = self.reg(Register::X_CH_RESULT) as i16 as f32;
+        let y = self.reg(Register::Y_CH_RESULT) as i16 as f32;
+        let z = self.reg(Register::Z_CH_RESULT) as i16 as f32;
+        let pair = match sensor.angle_en() {
+            AngleEn::NoAngle => None,
+            AngleEn::Xy => Some((x, y)),
+            AngleEn::Yz => Some((y, z)),
+            AngleEn::Zx => Some((z, x)),
+        };
+        if let Some((first, second)) = pair {
+            let mut angle = second.atan2(first).to_degrees();
+            if angle < 0.0 {
+                angle += 360.0;
+            }
+            let magnitude = ((first * first + second * second).sqrt() / 16.0).round();
+            self.set_reg(Register::ANGLE_RESULT, (angle * 16.0).round() as u16 % 5760);
+            self.set_reg(Register::MAGNITUDE_RESULT, magnitude.min(4095.0) as u16);
+            conv |= 0b1 << 12;
+        }
+
+        let set_count = ((conv >> 4) + 1) & 0b111;
+        conv = conv & !(0b111 << 4) | (set_count << 4) | (0b1 << 13);
+        self.set_reg(Register::CONV_STATUS, conv);
+
+        let checks = [
+            (
+                system.t_x_limit_check(),
+                Register::X_CH_RESULT,
+                Register::X_THRX_CONFIG,
+            ),
+            (
+                system.t_y_limit_check(),
+                Register::Y_CH_RESULT,
+                Register::Y_THRX_CONFIG,
+            ),
+            (
+                system.t_z_limit_check(),
+                Register::Z_CH_RESULT,
+                Register::Z_THRX_CONFIG,
+            ),
+        ];
+        let mut thx = 0;
+        for (bit, &(enabled, result, thresholds)) in checks.iter().enumerate() {
+            let value = (self.reg(result) as i16 >> 8) as i8;
+            let thresholds = self.reg(thresholds);
+            let hi = (thresholds >> 8) as u8 as i8;
+            let lo = thresholds as u8 as i8;
+            if enabled && (value > hi || value < lo) {
+                thx |= 0b1 << bit;
+            }
+        }
+        if device.t_limit_check_en() {
+            let value = (self.reg(Register::TEMP_RESULT) >> 8) as u8;
+            let thresholds = self.reg(Register::T_THRX_CONFIG);
+            if value > (thresholds >> 8) as u8 || value < thresholds as u8 {
+                thx |= 0b1 << 3;
+            }
+        }
+        let sys = self.reg(Register::SYS_STATUS) & !0b1111 | thx;
+        self.set_reg(Register::SYS_STATUS, sys);
+    }
+}
@@ -0,0 +1,52 @@
+use crate::status::SysStatus;
+
+/// SPI link quality counters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LinkStats {
+    /// SPI frames sent, including retries
+    pub frames: u32,
+
+    /// SDO frames whose CRC didn't match their content
+    pub crc_errors: u32,
+
+    /// SPI bus errors
+    pub spi_errors: u32,
+
+    /// Chip select pin errors
+    pub pin_errors: u32,
+
+    /// SDO frames reporting that the previous SDI frame was ignored (PREV_CRC_STAT)
+    pub rejected_frames: u32,
+
+    /// SYS_STATUS reads reporting a SDI CRC error (CRC_STAT)
+    pub device_crc_errors: u32,
+
+    /// SYS_STATUS reads reporting a SDI frame length error (FRAME_STAT)
+    pub device_frame_errors: u32,
+
+    /// Retries done by the retry policy
+    pub retries: u32,
+}
+
+impl LinkStats {
+    /// Frames which failed on the host side: CRC, SPI and pin errors
+    pub fn failed_frames(&self) -> u32 {
+        self.crc_errors
+            .saturating_add(self.spi_errors)
+            .saturating_add(self.pin_errors)
+    }
+
+    pub(crate) fn record_sys_status(&mut self, sys: SysStatus) {
+        if sys.crc_stat() {
+            bump(&mut self.device_crc_errors);
+        }
+        if sys.frame_stat() {
+            bump(&mut self.device_frame_errors);
+        }
+    }
+}
+
+pub(crate) fn bump(counter: &mut u32) {
+    *counter = counter.saturating_add(1);
+}
@@ -0,0 +1,368 @@
+use crate::limit_check::LimitCheck;
+
+/// Conversion status - CONV_STATUS
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConvStatus {
+    status: u16,
+}
+
+impl ConvStatus {
+    /// Creates status from u16 value
+    pub fn from_u16(status: u16) -> Self {
+        ConvStatus { status }
+    }
+
+    /// Convert status to u16 value
+    pub fn to_u16(&self) -> u16 {
+        self.status
+    }
+
+    /// Sets conversion data buffer ready
+    pub fn set_rdy(mut self, rdy: bool) -> Self {
+        self.status = self.status & !(0b1 << 13) | ((rdy as u16) << 13);
+        self
+    }
+
+    /// Sets new ANGLE_RESULT and MAGNITUDE_RESULT data
+    pub fn set_a(mut self, a: bool) -> Self {
+        self.status = self.status & !(0b1 << 12) | ((a as u16) << 12);
+        self
+    }
+
+    /// Sets new TEMP_RESULT data
+    pub fn set_t(mut self, t: bool) -> Self {
+        self.status = self.status & !(0b1 << 11) | ((t as u16) << 11);
+        self
+    }
+
+    /// Sets new Z_CH_RESULT data
+    pub fn set_z(mut self, z: bool) -> Self {
+        self.status = self.status & !(0b1 << 10) | ((z as u16) << 10);
+        self
+    }
+
+    /// Sets new Y_CH_RESULT data
+    pub fn set_y(mut self, y: bool) -> Self {
+        self.status = self.status & !(0b1 << 9) | ((y as u16) << 9);
+        self
+    }
+
+    /// Sets new X_CH_RESULT data
+    pub fn set_x(mut self, x: bool) -> Self {
+        self.status = self.status & !(0b1 << 8) | ((x as u16) << 8);
+        self
+    }
+
+    /// Sets rolling count of conversion data sets, 3 bits
+    pub fn set_set_count(mut self, set_count: u8) -> Self {
+        self.status = self.status & !(0b111 << 4) | (((set_count as u16) & 0b111) << 4);
+        self
+    }
+
+    /// Sets ALERT status of the conversion, 2 bits
+    pub fn set_alrt_status(mut self, alrt_status: u8) -> Self {
+        self.status = self.status & !0b11 | ((alrt_status as u16) & 0b11);
+        self
+    }
+
+    /// Conversion data buffer is ready to be read
+    pub fn rdy(&self) -> bool {
+        self.status & (0b1 << 13) != 0
+    }
+
+    /// ANGLE_RESULT and MAGNITUDE_RESULT hold new data
+    pub fn a(&self) -> bool {
+        self.status & (0b1 << 12) != 0
+    }
+
+    /// TEMP_RESULT holds new data
+    pub fn t(&self) -> bool {
+        self.status & (0b1 << 11) != 0
+    }
+
+    /// Z_CH_RESULT holds new data
+    pub fn z(&self) -> bool {
+        self.status & (0b1 << 10) != 0
+    }
+
+    /// Y_CH_RESULT holds new data
+    pub fn y(&self) -> bool {
+        self.status & (0b1 << 9) != 0
+    }
+
+    /// X_CH_RESULT holds new data
+    pub fn x(&self) -> bool {
+        self.status & (0b1 << 8) != 0
+    }
+
+    /// Rolling count of conversion data sets
+    pub fn set_count(&self) -> u8 {
+        ((self.status >> 4) & 0b111) as u8
+    }
+
+    /// ALERT status of the conversion
+    pub fn alrt_status(&self) -> u8 {
+        (self.status & 0b11) as u8
+    }
+}
+
+/// Analog front end status - AFE_STATUS
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AfeStatus {
+    status: u16,
+}
+
+impl AfeStatus {
+    /// Creates status from u16 value
+    pub fn from_u16(status: u16) -> Self {
+        AfeStatus { status }
+    }
+
+    /// Convert status to u16 value
+    pub fn to_u16(&self) -> u16 {
+        self.status
+    }
+
+    /// Sets device reset flag
+    pub fn set_cfg_reset(mut self, cfg_reset: bool) -> Self {
+        self.status = self.status & !(0b1 << 15) | ((cfg_reset as u16) << 15);
+        self
+    }
+
+    /// Sets Hall sensor bias or offset error
+    pub fn set_sens_stat(mut self, sens_stat: bool) -> Self {
+        self.status = self.status & !(0b1 << 12) | ((sens_stat as u16) << 12);
+        self
+    }
+
+    /// Sets temperature sensor error
+    pub fn set_temp_stat(mut self, temp_stat: bool) -> Self {
+        self.status = self.status & !(0b1 << 11) | ((temp_stat as u16) << 11);
+        self
+    }
+
+    /// Sets Z axis Hall element error
+    pub fn set_zhs_stat(mut self, zhs_stat: bool) -> Self {
+        self.status = self.status & !(0b1 << 10) | ((zhs_stat as u16) << 10);
+        self
+    }
+
+    /// Sets Y axis Hall element error
+    pub fn set_yhs_stat(mut self, yhs_stat: bool) -> Self {
+        self.status = self.status & !(0b1 << 9) | ((yhs_stat as u16) << 9);
+        self
+    }
+
+    /// Sets X axis Hall element error
+    pub fn set_xhs_stat(mut self, xhs_stat: bool) -> Self {
+        self.status = self.status & !(0b1 << 8) | ((xhs_stat as u16) << 8);
+        self
+    }
+
+    /// Sets trim data error
+    pub fn set_trim_stat(mut self, trim_stat: bool) -> Self {
+        self.status = self.status & !(0b1 << 1) | ((trim_stat as u16) << 1);
+        self
+    }
+
+    /// Sets LDO error
+    pub fn set_ldo_stat(mut self, ldo_stat: bool) -> Self {
+        self.status = self.status & !0b1 | (ldo_stat as u16);
+        self
+    }
+
+    /// Device has been reset and registers hold their default values
+    pub fn cfg_reset(&self) -> bool {
+        self.status & (0b1 << 15) != 0
+    }
+
+    /// Hall sensor bias or offset error
+    pub fn sens_stat(&self) -> bool {
+        self.status & (0b1 << 12) != 0
+    }
+
+    /// Temperature sensor error
+    pub fn temp_stat(&self) -> bool {
+        self.status & (0b1 << 11) != 0
+    }
+
+    /// Z axis Hall element error
+    pub fn zhs_stat(&self) -> bool {
+        self.status & (0b1 << 10) != 0
+    }
+
+    /// Y axis Hall element error
+    pub fn yhs_stat(&self) -> bool {
+        self.status & (0b1 << 9) != 0
+    }
+
+    /// X axis Hall element error
+    pub fn xhs_stat(&self) -> bool {
+        self.status & (0b1 << 8) != 0
+    }
+
+    /// Trim data error
+    pub fn trim_stat(&self) -> bool {
+        self.status & (0b1 << 1) != 0
+    }
+
+    /// LDO error
+    pub fn ldo_stat(&self) -> bool {
+        self.status & 0b1 != 0
+    }
+}
+
+/// System status - SYS_STATUS
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SysStatus {
+    status: u16,
+}
+
+impl SysStatus {
+    /// Creates status from u16 value
+    pub fn from_u16(status: u16) -> Self {
+        SysStatus { status }
+    }
+
+    /// Convert status to u16 value
+    pub fn to_u16(&self) -> u16 {
+        self.status
+    }
+
+    /// Sets level of the ALERT pin
+    pub fn set_alrt_lvl(mut self, alrt_lvl: bool) -> Self {
+        self.status = self.status & !(0b1 << 15) | ((alrt_lvl as u16) << 15);
+        self
+    }
+
+    /// Sets ALERT output drive error
+    pub fn set_alrt_drv(mut self, alrt_drv: bool) -> Self {
+        self.status = self.status & !(0b1 << 14) | ((alrt_drv as u16) << 14);
+        self
+    }
+
+    /// Sets SDO output drive error
+    pub fn set_sdo_drv(mut self, sdo_drv: bool) -> Self {
+        self.status = self.status & !(0b1 << 13) | ((sdo_drv as u16) << 13);
+        self
+    }
+
+    /// Sets SDI frame CRC error
+    pub fn set_crc_stat(mut self, crc_stat: bool) -> Self {
+        self.status = self.status & !(0b1 << 12) | ((crc_stat as u16) << 12);
+        self
+    }
+
+    /// Sets SCLK cycle count error
+    pub fn set_frame_stat(mut self, frame_stat: bool) -> Self {
+        self.status = self.status & !(0b1 << 11) | ((frame_stat as u16) << 11);
+        self
+    }
+
+    /// Sets current operating mode, 3 bits
+    pub fn set_operating_stat(mut self, operating_stat: u8) -> Self {
+        self.status = self.status & !(0b111 << 8) | (((operating_stat as u16) & 0b111) << 8);
+        self
+    }
+
+    /// Sets VCC overvoltage
+    pub fn set_vcc_ov(mut self, vcc_ov: bool) -> Self {
+        self.status = self.status & !(0b1 << 5) | ((vcc_ov as u16) << 5);
+        self
+    }
+
+    /// Sets VCC undervoltage
+    pub fn set_vcc_uv(mut self, vcc_uv: bool) -> Self {
+        self.status = self.status & !(0b1 << 4) | ((vcc_uv as u16) << 4);
+        self
+    }
+
+    /// Sets failed temperature limit check
+    pub fn set_temp_thx(mut self, temp_thx: bool) -> Self {
+        self.status = self.status & !(0b1 << 3) | ((temp_thx as u16) << 3);
+        self
+    }
+
+    /// Sets failed Z channel limit check
+    pub fn set_zch_thx(mut self, zch_thx: bool) -> Self {
+        self.status = self.status & !(0b1 << 2) | ((zch_thx as u16) << 2);
+        self
+    }
+
+    /// Sets failed Y channel limit check
+    pub fn set_ych_thx(mut self, ych_thx: bool) -> Self {
+        self.status = self.status & !(0b1 << 1) | ((ych_thx as u16) << 1);
+        self
+    }
+
+    /// Sets failed X channel limit check
+    pub fn set_xch_thx(mut self, xch_thx: bool) -> Self {
+        self.status = self.status & !0b1 | (xch_thx as u16);
+        self
+    }
+
+    /// Level of the ALERT pin
+    pub fn alrt_lvl(&self) -> bool {
+        self.status & (0b1 << 15) != 0
+    }
+
+    /// ALERT output drive error
+    pub fn alrt_drv(&self) -> bool {
+        self.status & (0b1 << 14) != 0
+    }
+
+    /// SDO output drive error
+    pub fn sdo_drv(&self) -> bool {
+        self.status & (0b1 << 13) != 0
+    }
+
+    /// CRC error detected in a SDI frame
+    pub fn crc_stat(&self) -> bool {
+        self.status & (0b1 << 12) != 0
+    }
+
+    /// Frame with a wrong number of SCLK cycles detected
+    pub fn frame_stat(&self) -> bool {
+        self.status & (0b1 << 11) != 0
+    }
+
+    /// Current operating mode, see OperatingMode
+    pub fn operating_stat(&self) -> u8 {
+        ((self.status >> 8) & 0b111) as u8
+    }
+
+    /// VCC overvoltage
+    pub fn vcc_ov(&self) -> bool {
+        self.status & (0b1 << 5) != 0
+    }
+
+    /// VCC undervoltage
+    pub fn vcc_uv(&self) -> bool {
+        self.status & (0b1 << 4) != 0
+    }
+
+    /// Temperature limit check failed
+    pub fn temp_thx(&self) -> bool {
+        self.status & (0b1 << 3) != 0
+    }
+
+    /// Z channel limit check failed
+    pub fn zch_thx(&self) -> bool {
+        self.status & (0b1 << 2) != 0
+    }
+
+    /// Y channel limit check failed
+    pub fn ych_thx(&self) -> bool {
+        self.status & (0b1 << 1) != 0
+    }
+
+    /// X channel limit check failed
+    pub fn xch_thx(&self) -> bool {
+        self.status & 0b1 != 0
+    }
+
+    /// Limit check results
+    pub fn limit_check(&self) -> LimitCheck {
+        LimitCheck::from_sys_status(*self)
+    }
+}
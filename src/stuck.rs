@@ -0,0 +1,226 @@
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::device_config::{ConvAvg, OperatingMode};
+use crate::health::Fault;
+use crate::retry::Backoff;
+use crate::{ExtError, Tmag5170};
+
+/// Outputs which didn't change for longer than the detector allows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct StuckOutputs {
+    /// X_CH_RESULT is frozen
+    pub x: bool,
+
+    /// Y_CH_RESULT is frozen
+    pub y: bool,
+
+    /// Z_CH_RESULT is frozen
+    pub z: bool,
+
+    /// Angle of the special read is frozen
+    pub angle: bool,
+
+    /// Magnitude of the special read is frozen
+    pub magnitude: bool,
+
+    /// CONV_STATUS.SET_COUNT stopped advancing in active mode
+    pub set_count: bool,
+}
+
+impl StuckOutputs {
+    /// Returns true if any output is frozen
+    pub fn any(&self) -> bool {
+        self.x || self.y || self.z || self.angle || self.magnitude || self.set_count
+    }
+
+    /// Fault to feed to the health monitor if an output is frozen
+    pub fn fault(&self) -> Option<Fault> {
+        if self.any() {
+            Some(Fault::StuckOutput)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Channel {
+    last: Option<u16>,
+    run: u16,
+}
+
+impl Channel {
+    fn update(&mut self, value: u16, limit: u16) -> bool {
+        if self.last == Some(value) {
+            self.run = self.run.saturating_add(1);
+        } else {
+            self.last = Some(value);
+            self.run = 1;
+        }
+        self.run > limit
+    }
+}
+
+/// Flags results which repeat the same code for more samples than the noise makes plausible
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StuckDetector {
+    samples: u16,
+    x: Channel,
+    y: Channel,
+    z: Channel,
+    angle: Channel,
+    magnitude: Channel,
+    set_count: Channel,
+    last_set_count: Option<u8>,
+    outputs: StuckOutputs,
+}
+
+impl StuckDetector {
+    /// Creates detector which allows `samples` identical results without averaging.
+    /// The allowance grows with the square root of the ConvAvg averaging, which lowers the noise
+    pub fn new(samples: u16) -> Self {
+        StuckDetector {
+            samples,
+            x: Channel::default(),
+            y: Channel::default(),
+            z: Channel::default(),
+            angle: Channel::default(),
+            magnitude: Channel::default(),
+            set_count: Channel::default(),
+            last_set_count: None,
+            outputs: StuckOutputs::default(),
+        }
+    }
+
+    /// Number of identical results allowed at the averaging, a reserved code is taken as 32x
+    pub fn limit(&self, conv_avg: Option<ConvAvg>) -> u16 {
+        let factor = match conv_avg {
+            Some(ConvAvg::Avg1x) | Some(ConvAvg::Avg2x) => 1,
+            Some(ConvAvg::Avg4x) => 2,
+            Some(ConvAvg::Avg8x) => 3,
+            Some(ConvAvg::Avg16x) => 4,
+            Some(ConvAvg::Avg32x) | None => 6,
+        };
+        self.samples.saturating_mul(factor)
+    }
+
+    /// Feeds one set of magnetic results
+    pub fn update_mag(
+        &mut self,
+        conv_avg: Option<ConvAvg>,
+        x: i16,
+        y: i16,
+        z: i16,
+    ) -> StuckOutputs {
+        let limit = self.limit(conv_avg);
+        self.outputs.x = self.x.update(x as u16, limit);
+        self.outputs.y = self.y.update(y as u16, limit);
+        self.outputs.z = self.z.update(z as u16, limit);
+        self.outputs
+    }
+
+    /// Feeds one angle and magnitude special read
+    pub fn update_am(
+        &mut self,
+        conv_avg: Option<ConvAvg>,
+        angle: u16,
+        magnitude: u16,
+    ) -> StuckOutputs {
+        let limit = self.limit(conv_avg);
+        self.outputs.angle = self.angle.update(angle, limit);
+        self.outputs.magnitude = self.magnitude.update(magnitude, limit);
+        self.outputs
+    }
+
+    /// Feeds one SET_COUNT read in active mode, reads must be spaced by more than a conversion
+    pub fn update_set_count(&mut self, set_count: u8) -> StuckOutputs {
+        self.outputs.set_count = self.set_count.update(set_count as u16, self.samples);
+        self.outputs
+    }
+
+    /// Returns true if SET_COUNT differs from the one of the previous call
+    fn converted(&mut self, set_count: u8) -> bool {
+        let converted = self.last_set_count != Some(set_count);
+        self.last_set_count = Some(set_count);
+        converted
+    }
+
+    /// Outputs flagged by the latest samples
+    pub fn stuck(&self) -> StuckOutputs {
+        self.outputs
+    }
+
+    /// Forgets all samples, e.g. after a configuration change
+    pub fn reset(&mut self) {
+        *self = StuckDetector::new(self.samples);
+    }
+}
+
+impl Default for StuckDetector {
+    fn default() -> Self {
+        StuckDetector::new(8)
+    }
+}
+
+impl<SPI, CS, B, E, PE> Tmag5170<SPI, CS, B>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    B: Backoff,
+{
+    /// Reads magnetic registers in raw format and CONV_STATUS, and feeds them to the detector.
+    /// SET_COUNT is checked while the device converts continuously (Active, WakeupAndSleep).
+    /// In the other modes results only change after a trigger, they are fed only if SET_COUNT
+    /// moved since the previous call
+    pub fn read_mag_registers_monitored(
+        &mut self,
+        detector: &mut StuckDetector,
+    ) -> Result<(i16, i16, i16), ExtError<E, PE>> {
+        let (x, y, z) = self.read_mag_registers()?;
+        let conv = self.read_conv_status()?;
+        let device = self.config().device;
+
+        let converted = detector.converted(conv.set_count());
+        if self.converts_continuously() {
+            detector.update_set_count(conv.set_count());
+            detector.update_mag(device.conv_avg(), x, y, z);
+        } else {
+            detector.set_count = Channel::default();
+            detector.outputs.set_count = false;
+            if converted {
+                detector.update_mag(device.conv_avg(), x, y, z);
+            }
+        }
+
+        Ok((x, y, z))
+    }
+
+    /// Reads angle and magnitude as special read and feeds them to the detector while the
+    /// device converts continuously. The angle and magnitude samples are dropped otherwise
+    pub fn read_am_monitored(
+        &mut self,
+        detector: &mut StuckDetector,
+    ) -> Result<(u16, u16), ExtError<E, PE>> {
+        let (a, m) = self.read_am()?;
+        if self.converts_continuously() {
+            detector.update_am(self.config().device.conv_avg(), a, m);
+        } else {
+            detector.angle = Channel::default();
+            detector.magnitude = Channel::default();
+            detector.outputs.angle = false;
+            detector.outputs.magnitude = false;
+        }
+
+        Ok((a, m))
+    }
+
+    fn converts_continuously(&self) -> bool {
+        matches!(
+            self.config().device.operating_mode(),
+            Some(OperatingMode::Active) | Some(OperatingMode::WakeupAndSleep)
+        )
+    }
+}
@@ -0,0 +1,177 @@
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+
+use crate::retry::Backoff;
+use crate::status::SysStatus;
+use crate::{ExtError, Tmag5170};
+
+/// Supply fault reported by SYS_STATUS
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SupplyFault {
+    /// VCC overvoltage (VCC_OV)
+    Overvoltage,
+
+    /// VCC undervoltage (VCC_UV)
+    Undervoltage,
+}
+
+/// Debounced change of a supply fault
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SupplyEvent {
+    /// Supply fault which changed
+    pub fault: SupplyFault,
+
+    /// True if the fault is now present, false if it went away
+    pub active: bool,
+}
+
+/// Supply events produced by one status sample
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SupplyEvents {
+    events: [Option<SupplyEvent>; 2],
+    idx: usize,
+}
+
+impl SupplyEvents {
+    fn none() -> Self {
+        SupplyEvents {
+            events: [None; 2],
+            idx: 0,
+        }
+    }
+}
+
+impl Iterator for SupplyEvents {
+    type Item = SupplyEvent;
+
+    fn next(&mut self) -> Option<SupplyEvent> {
+        while self.idx < self.events.len() {
+            self.idx += 1;
+            if let Some(event) = self.events[self.idx - 1] {
+                return Some(event);
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Debounce {
+    count: u8,
+    active: bool,
+}
+
+impl Debounce {
+    fn update(&mut self, raw: bool, samples: u8) -> Option<bool> {
+        if raw == self.active {
+            self.count = 0;
+            return None;
+        }
+
+        self.count = self.count.saturating_add(1);
+        if self.count >= samples {
+            self.count = 0;
+            self.active = raw;
+            Some(raw)
+        } else {
+            None
+        }
+    }
+}
+
+/// Debounces the VCC_OV and VCC_UV bits of SYS_STATUS
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SupplyMonitor {
+    samples: u8,
+    ov: Debounce,
+    uv: Debounce,
+}
+
+impl SupplyMonitor {
+    /// Creates monitor which reports a change after `samples` consecutive equal samples
+    pub fn new(samples: u8) -> Self {
+        SupplyMonitor {
+            samples: samples.max(1),
+            ov: Debounce::default(),
+            uv: Debounce::default(),
+        }
+    }
+
+    /// Feeds one SYS_STATUS sample, returns the debounced changes
+    pub fn update(&mut self, sys: SysStatus) -> SupplyEvents {
+        let ov = self.ov.update(sys.vcc_ov(), self.samples);
+        let uv = self.uv.update(sys.vcc_uv(), self.samples);
+
+        SupplyEvents {
+            events: [
+                ov.map(|active| SupplyEvent {
+                    fault: SupplyFault::Overvoltage,
+                    active,
+                }),
+                uv.map(|active| SupplyEvent {
+                    fault: SupplyFault::Undervoltage,
+                    active,
+                }),
+            ],
+            idx: 0,
+        }
+    }
+
+    /// Is debounced overvoltage present
+    pub fn is_overvoltage(&self) -> bool {
+        self.ov.active
+    }
+
+    /// Is debounced undervoltage present
+    pub fn is_undervoltage(&self) -> bool {
+        self.uv.active
+    }
+}
+
+impl Default for SupplyMonitor {
+    fn default() -> Self {
+        SupplyMonitor::new(3)
+    }
+}
+
+impl<SPI, CS, B, E, PE> Tmag5170<SPI, CS, B>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+    B: Backoff,
+{
+    /// Routes supply faults to the ALERT pin (STATUS_ALRT) on top of the current AlertConfig
+    pub fn enable_supply_alert(&mut self) -> Result<(), ExtError<E, PE>> {
+        let config = self.config().alert.set_status_alrt_enable(true);
+        self.apply_alert_config(config)
+    }
+
+    /// Reads SYS_STATUS and feeds it to the monitor
+    pub fn poll_supply(
+        &mut self,
+        monitor: &mut SupplyMonitor,
+    ) -> Result<SupplyEvents, ExtError<E, PE>> {
+        let sys = self.read_sys_status()?;
+
+        Ok(monitor.update(sys))
+    }
+
+    /// Reads SYS_STATUS only while the active low ALERT pin is asserted, otherwise the
+    /// monitor is left unchanged. A released pin doesn't clear a fault, use
+    /// [`Tmag5170::poll_supply`] to see it go away
+    pub fn poll_supply_on_alert<P: InputPin>(
+        &mut self,
+        alert: &P,
+        monitor: &mut SupplyMonitor,
+    ) -> Result<SupplyEvents, ExtError<E, PE>> {
+        // A pin which can't be read is treated as asserted
+        if alert.is_low().unwrap_or(true) {
+            self.poll_supply(monitor)
+        } else {
+            Ok(SupplyEvents::none())
+        }
+    }
+}
@@ -1,4 +1,7 @@
 /// Selects a safety diagnostic mode run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum DiagSel {
     /// Run all data path diagnostics all together (default)
     AllData = 0x00,
@@ -13,8 +16,22 @@ pub enum DiagSel {
     EnabledDataInSeq = 0x03,
 }
 
+impl DiagSel {
+    pub(crate) fn from_bits(bits: u16) -> Self {
+        match bits {
+            0x00 => DiagSel::AllData,
+            0x01 => DiagSel::EnabledData,
+            0x02 => DiagSel::AllDataInSeq,
+            _ => DiagSel::EnabledDataInSeq,
+        }
+    }
+}
+
 /// Selects a condition which initiates a single conversion
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum TriggerMode {
     /// Conversion Start at SPI Command Bits (default)
     Spi = 0x00,
@@ -26,8 +43,22 @@ pub enum TriggerMode {
     Alert = 0x02,
 }
 
+impl TriggerMode {
+    pub(crate) fn from_bits(bits: u16) -> Option<Self> {
+        match bits {
+            0x00 => Some(TriggerMode::Spi),
+            0x01 => Some(TriggerMode::Cs),
+            0x02 => Some(TriggerMode::Alert),
+            _ => None,
+        }
+    }
+}
+
 /// Data Type to be accessed from results registers via SPI
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum DataType {
     /// Default 32-bit Register Access
     Default = 0x00,
@@ -54,12 +85,31 @@ pub enum DataType {
     AM = 0x07,
 }
 
+impl DataType {
+    pub(crate) fn from_bits(bits: u16) -> Self {
+        match bits {
+            0x00 => DataType::Default,
+            0x01 => DataType::XY,
+            0x02 => DataType::XZ,
+            0x03 => DataType::ZY,
+            0x04 => DataType::XT,
+            0x05 => DataType::YT,
+            0x06 => DataType::ZT,
+            _ => DataType::AM,
+        }
+    }
+}
+
 /// Configure Device Operation Modes - SYSTEM_CONFIG
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SystemConfig {
     config: u16,
 }
 
 impl SystemConfig {
+    /// Reserved bits of SYSTEM_CONFIG
+    pub const RESERVED_BITS: u16 = 0xc818;
+
     /// Creates default config
     pub fn new() -> Self {
         let config = 0x00;
@@ -117,6 +167,41 @@ impl SystemConfig {
         self.config = self.config & !(0b1) | (x_limit_check as u16);
         self
     }
+
+    /// Get DiagSel field
+    pub fn diag_sel(&self) -> DiagSel {
+        DiagSel::from_bits((self.config >> 12) & 0b11)
+    }
+
+    /// Get TriggerMode field, `None` if it holds a reserved code
+    pub fn trigger_mode(&self) -> Option<TriggerMode> {
+        TriggerMode::from_bits((self.config >> 9) & 0b11)
+    }
+
+    /// Get DataType field
+    pub fn data_type(&self) -> DataType {
+        DataType::from_bits((self.config >> 6) & 0b111)
+    }
+
+    /// Are AFE Diagnostic Tests enabled
+    pub fn diag_en(&self) -> bool {
+        self.config & (0b1 << 5) != 0
+    }
+
+    /// Is magnetic field limit check on Z axis enabled
+    pub fn t_z_limit_check(&self) -> bool {
+        self.config & (0b1 << 2) != 0
+    }
+
+    /// Is magnetic field limit check on Y axis enabled
+    pub fn t_y_limit_check(&self) -> bool {
+        self.config & (0b1 << 1) != 0
+    }
+
+    /// Is magnetic field limit check on X axis enabled
+    pub fn t_x_limit_check(&self) -> bool {
+        self.config & 0b1 != 0
+    }
 }
 
 impl Default for SystemConfig {
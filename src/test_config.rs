@@ -0,0 +1,119 @@
+/// Device version, read only
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Version {
+    /// TMAG5170A1 (±25mT, ±50mT, ±100mT)
+    A1 = 0x00,
+
+    /// TMAG5170A2 (±133mT, ±200mT, ±300mT)
+    A2 = 0x01,
+}
+
+impl Version {
+    pub(crate) fn from_bits(bits: u16) -> Option<Self> {
+        match bits {
+            0x00 => Some(Version::A1),
+            0x01 => Some(Version::A2),
+            _ => None,
+        }
+    }
+}
+
+/// Oscillator error counter control
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OscCntCtl {
+    /// Reset the counter (default)
+    Reset = 0x00,
+
+    /// Start the counter with HFOSC
+    StartHfosc = 0x01,
+
+    /// Start the counter with LFOSC
+    StartLfosc = 0x02,
+
+    /// Stop the counter
+    Stop = 0x03,
+}
+
+impl OscCntCtl {
+    pub(crate) fn from_bits(bits: u16) -> Self {
+        match bits {
+            0x00 => OscCntCtl::Reset,
+            0x01 => OscCntCtl::StartHfosc,
+            0x02 => OscCntCtl::StartLfosc,
+            _ => OscCntCtl::Stop,
+        }
+    }
+}
+
+/// Test configuration - TEST_CONFIG
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TestConfig {
+    config: u16,
+}
+
+impl TestConfig {
+    /// Reserved bits of TEST_CONFIG
+    pub const RESERVED_BITS: u16 = 0xffc8;
+
+    /// Creates default config
+    pub fn new() -> Self {
+        let config = 0x00;
+        TestConfig { config }
+    }
+
+    /// Creates config from u16 value
+    pub fn form_u16(config: u16) -> Self {
+        TestConfig { config }
+    }
+
+    /// Convert config to u16 value
+    pub fn to_u16(&self) -> u16 {
+        self.config
+    }
+
+    /// Disables CRC of the SPI frames
+    pub fn set_crc_dis(mut self, crc_dis: bool) -> Self {
+        self.config = self.config & !(0b1 << 2) | ((crc_dis as u16) << 2);
+        self
+    }
+
+    /// Set OscCntCtl field
+    pub fn set_osc_cnt_ctl(mut self, osc_cnt_ctl: OscCntCtl) -> Self {
+        self.config = self.config & !(0b11) | (osc_cnt_ctl as u16);
+        self
+    }
+
+    /// Set Version field, which the device ignores on writes
+    #[cfg(feature = "serde")]
+    pub(crate) fn set_ver(mut self, ver: Option<Version>) -> Self {
+        let ver = ver.map_or(0, |ver| ver as u16);
+        self.config = self.config & !(0b11 << 4) | (ver << 4);
+        self
+    }
+
+    /// Get Version field, `None` if it holds an unknown code
+    pub fn ver(&self) -> Option<Version> {
+        Version::from_bits((self.config >> 4) & 0b11)
+    }
+
+    /// Is CRC of the SPI frames disabled
+    pub fn crc_dis(&self) -> bool {
+        self.config & (0b1 << 2) != 0
+    }
+
+    /// Get OscCntCtl field
+    pub fn osc_cnt_ctl(&self) -> OscCntCtl {
+        OscCntCtl::from_bits(self.config & 0b11)
+    }
+}
+
+impl Default for TestConfig {
+    fn default() -> Self {
+        TestConfig::new()
+    }
+}
@@ -0,0 +1,83 @@
+use core::fmt;
+
+use crate::frame::{self, FrameStatus};
+use crate::system_config::DataType;
+use crate::{ExtError, Register};
+
+/// Outcome of a SPI frame: frame status if the SDO CRC matched, the failure otherwise
+struct Outcome<'a, E, PE>(&'a Result<(), ExtError<E, PE>>, FrameStatus);
+
+impl<E, PE> fmt::Display for Outcome<'_, E, PE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Ok(()) => write!(f, "crc=ok stat={:#05x}", self.1.to_u16()),
+            Err(ExtError::Crc) => write!(f, "crc=BAD"),
+            Err(ExtError::NotPresent) => write!(f, "not present"),
+            Err(ExtError::Spi(_)) => write!(f, "SPI error"),
+            Err(ExtError::Pin(_)) => write!(f, "chip select pin error"),
+            Err(_) => write!(f, "failed"),
+        }
+    }
+}
+
+/// Data of a SDO frame: register value of a regular frame, both channels of a special frame
+struct Data<'a>(&'a [u8; 4], DataType);
+
+impl fmt::Display for Data<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            DataType::Default => write!(f, "{:#06x}", frame::register_value(self.0)),
+            _ => {
+                let (ch1, ch2) = frame::special_values(self.0);
+                write!(f, "{:#05x} {:#05x}", ch1, ch2)
+            }
+        }
+    }
+}
+
+pub(crate) fn write<E, PE>(
+    reg: Register,
+    value: u16,
+    cmd: u8,
+    buffer: &[u8; 4],
+    res: &Result<(), ExtError<E, PE>>,
+    data_type: DataType,
+) {
+    log::trace!(
+        "W {:?} = {:#06x} cmd={:#x} {}",
+        reg,
+        value,
+        cmd,
+        Outcome(res, frame::parse_status(buffer, data_type))
+    );
+}
+
+pub(crate) fn read<E, PE>(
+    reg: Register,
+    cmd: u8,
+    buffer: &[u8; 4],
+    res: &Result<(), ExtError<E, PE>>,
+    data_type: DataType,
+) {
+    log::trace!(
+        "R {:?} = {} cmd={:#x} {}",
+        reg,
+        Data(buffer, data_type),
+        cmd,
+        Outcome(res, frame::parse_status(buffer, data_type))
+    );
+}
+
+pub(crate) fn special<E, PE>(
+    cmd: u8,
+    buffer: &[u8; 4],
+    res: &Result<(), ExtError<E, PE>>,
+    data_type: DataType,
+) {
+    log::trace!(
+        "R special = {} cmd={:#x} {}",
+        Data(buffer, data_type),
+        cmd,
+        Outcome(res, frame::parse_status(buffer, data_type))
+    );
+}
@@ -0,0 +1,321 @@
+use crate::alert_config::{AlertConfig, AlertMode};
+use crate::device_config::DeviceConfig;
+use crate::sensor_config::{AngleEn, SensorConfig};
+use crate::system_config::{DataType, SystemConfig};
+
+/// Maximum number of issues kept by [`Issues`]
+pub const MAX_ISSUES: usize = 24;
+
+/// Configuration register
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigRegister {
+    /// DEVICE_CONFIG
+    Device,
+
+    /// SENSOR_CONFIG
+    Sensor,
+
+    /// SYSTEM_CONFIG
+    System,
+
+    /// ALERT_CONFIG
+    Alert,
+}
+
+/// Configuration field which can hold a reserved code
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Field {
+    /// DEVICE_CONFIG.CONV_AVG
+    ConvAvg,
+
+    /// DEVICE_CONFIG.MAG_TEMPCO
+    MagTempco,
+
+    /// DEVICE_CONFIG.OPERATING_MODE
+    OperatingMode,
+
+    /// SENSOR_CONFIG.SLEEPTIME
+    SleepTime,
+
+    /// SENSOR_CONFIG.X_RANGE
+    XRange,
+
+    /// SENSOR_CONFIG.Y_RANGE
+    YRange,
+
+    /// SENSOR_CONFIG.Z_RANGE
+    ZRange,
+
+    /// SYSTEM_CONFIG.TRIGGER_MODE
+    TriggerMode,
+}
+
+/// Magnetic axis
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Axis {
+    /// X axis
+    X,
+
+    /// Y axis
+    Y,
+
+    /// Z axis
+    Z,
+}
+
+/// Contradictory or invalid configuration setting
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigIssue {
+    /// Field holds a code marked as reserved in the datasheet
+    ReservedValue(Field),
+
+    /// Reserved bits of the register are set
+    ReservedBits(ConfigRegister, u16),
+
+    /// Angle calculation uses an axis which is not enabled by MagChEn
+    AngleAxisDisabled(AngleEn, Axis),
+
+    /// DataType::AM selected while angle calculation is off
+    AmWithoutAngle,
+
+    /// 12-bit DataType reads a magnetic channel which is not enabled by MagChEn
+    DataTypeAxisDisabled(DataType, Axis),
+
+    /// 12-bit DataType reads the temperature channel while T_EN is off
+    DataTypeTempDisabled(DataType),
+
+    /// Magnetic field limit check enabled on a disabled axis
+    LimitCheckAxisDisabled(Axis),
+
+    /// Temperature limit check enabled while T_EN is off
+    TempLimitCheckDisabled,
+
+    /// Threshold alert enabled on a disabled axis
+    ThresholdAlertAxisDisabled(Axis),
+
+    /// Temperature threshold alert enabled while T_EN is off
+    TempThresholdAlertDisabled,
+
+    /// RSLT_ALRT has no effect in comparator mode
+    ResultAlertInComparatorMode,
+}
+
+/// What the driver does when a configuration has issues
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ValidationMode {
+    /// Nothing is written, the first issue is returned as an error
+    Reject,
+
+    /// Configuration is written, the issues are returned
+    Warn,
+}
+
+/// List of configuration issues
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Issues {
+    issues: [Option<ConfigIssue>; MAX_ISSUES],
+}
+
+impl Issues {
+    fn new() -> Self {
+        Issues {
+            issues: [None; MAX_ISSUES],
+        }
+    }
+
+    fn push(&mut self, issue: ConfigIssue) {
+        if let Some(slot) = self.issues.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(issue);
+        }
+    }
+
+    /// Number of issues
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns true if the configuration has no issues
+    pub fn is_empty(&self) -> bool {
+        self.issues[0].is_none()
+    }
+
+    /// First issue found
+    pub fn first(&self) -> Option<ConfigIssue> {
+        self.issues[0]
+    }
+
+    /// Iterates over the issues
+    pub fn iter(&self) -> impl Iterator<Item = ConfigIssue> + '_ {
+        self.issues.iter().map_while(|issue| *issue)
+    }
+}
+
+/// Complete set of configuration registers
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ConfigSet {
+    /// DEVICE_CONFIG
+    pub device: DeviceConfig,
+
+    /// SENSOR_CONFIG
+    pub sensor: SensorConfig,
+
+    /// SYSTEM_CONFIG
+    pub system: SystemConfig,
+
+    /// ALERT_CONFIG
+    pub alert: AlertConfig,
+}
+
+impl ConfigSet {
+    /// Creates config set from the four configuration registers
+    pub fn new(
+        device: DeviceConfig,
+        sensor: SensorConfig,
+        system: SystemConfig,
+        alert: AlertConfig,
+    ) -> Self {
+        ConfigSet {
+            device,
+            sensor,
+            system,
+            alert,
+        }
+    }
+
+    /// Checks the configuration for reserved codes and contradictory settings
+    pub fn validate(&self) -> Issues {
+        let mut issues = Issues::new();
+
+        self.check_reserved(&mut issues);
+
+        let ch = self.sensor.mag_ch_en();
+        let enabled = |axis| match axis {
+            Axis::X => ch.enables_x(),
+            Axis::Y => ch.enables_y(),
+            Axis::Z => ch.enables_z(),
+        };
+        let t_en = self.device.t_en();
+
+        let angle_en = self.sensor.angle_en();
+        let angle_axes = match angle_en {
+            AngleEn::NoAngle => None,
+            AngleEn::Xy => Some((Axis::X, Axis::Y)),
+            AngleEn::Yz => Some((Axis::Y, Axis::Z)),
+            AngleEn::Zx => Some((Axis::Z, Axis::X)),
+        };
+        if let Some((a, b)) = angle_axes {
+            for &axis in [a, b].iter() {
+                if !enabled(axis) {
+                    issues.push(ConfigIssue::AngleAxisDisabled(angle_en, axis));
+                }
+            }
+        }
+
+        let data_type = self.system.data_type();
+        let (data_axes, data_temp): (&[Axis], bool) = match data_type {
+            DataType::Default => (&[], false),
+            DataType::XY => (&[Axis::X, Axis::Y], false),
+            DataType::XZ => (&[Axis::X, Axis::Z], false),
+            DataType::ZY => (&[Axis::Z, Axis::Y], false),
+            DataType::XT => (&[Axis::X], true),
+            DataType::YT => (&[Axis::Y], true),
+            DataType::ZT => (&[Axis::Z], true),
+            DataType::AM => (&[], false),
+        };
+        if data_type == DataType::AM && angle_en == AngleEn::NoAngle {
+            issues.push(ConfigIssue::AmWithoutAngle);
+        }
+        for &axis in data_axes {
+            if !enabled(axis) {
+                issues.push(ConfigIssue::DataTypeAxisDisabled(data_type, axis));
+            }
+        }
+        if data_temp && !t_en {
+            issues.push(ConfigIssue::DataTypeTempDisabled(data_type));
+        }
+
+        let limit_checks = [
+            (Axis::X, self.system.t_x_limit_check()),
+            (Axis::Y, self.system.t_y_limit_check()),
+            (Axis::Z, self.system.t_z_limit_check()),
+        ];
+        for &(axis, check) in limit_checks.iter() {
+            if check && !enabled(axis) {
+                issues.push(ConfigIssue::LimitCheckAxisDisabled(axis));
+            }
+        }
+        if self.device.t_limit_check_en() && !t_en {
+            issues.push(ConfigIssue::TempLimitCheckDisabled);
+        }
+
+        let threshold_alerts = [
+            (Axis::X, self.alert.x_thrx_alrt_enable()),
+            (Axis::Y, self.alert.y_thrx_alrt_enable()),
+            (Axis::Z, self.alert.z_thrx_alrt_enable()),
+        ];
+        for &(axis, alert) in threshold_alerts.iter() {
+            if alert && !enabled(axis) {
+                issues.push(ConfigIssue::ThresholdAlertAxisDisabled(axis));
+            }
+        }
+        if self.alert.t_thrx_alrt_enable() && !t_en {
+            issues.push(ConfigIssue::TempThresholdAlertDisabled);
+        }
+
+        if self.alert.alert_mode() == AlertMode::Comparator && self.alert.rslt_alrt_enable() {
+            issues.push(ConfigIssue::ResultAlertInComparatorMode);
+        }
+
+        issues
+    }
+
+    fn check_reserved(&self, issues: &mut Issues) {
+        let reserved = [
+            (self.device.conv_avg().is_none(), Field::ConvAvg),
+            (self.device.mag_tempco().is_none(), Field::MagTempco),
+            (self.device.operating_mode().is_none(), Field::OperatingMode),
+            (self.sensor.sleep_time().is_none(), Field::SleepTime),
+            (self.sensor.x_range().is_none(), Field::XRange),
+            (self.sensor.y_range().is_none(), Field::YRange),
+            (self.sensor.z_range().is_none(), Field::ZRange),
+            (self.system.trigger_mode().is_none(), Field::TriggerMode),
+        ];
+        for &(is_reserved, field) in reserved.iter() {
+            if is_reserved {
+                issues.push(ConfigIssue::ReservedValue(field));
+            }
+        }
+
+        let reserved_bits = [
+            (
+                ConfigRegister::Device,
+                self.device.to_u16() & DeviceConfig::RESERVED_BITS,
+            ),
+            (
+                ConfigRegister::Sensor,
+                self.sensor.to_u16() & SensorConfig::RESERVED_BITS,
+            ),
+            (
+                ConfigRegister::System,
+                self.system.to_u16() & SystemConfig::RESERVED_BITS,
+            ),
+            (
+                ConfigRegister::Alert,
+                self.alert.to_u16() & AlertConfig::RESERVED_BITS,
+            ),
+        ];
+        for &(register, bits) in reserved_bits.iter() {
+            if bits != 0 {
+                issues.push(ConfigIssue::ReservedBits(register, bits));
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use tmag5170::angle_check::{AngleCheck, AngleTolerance};
+use tmag5170::device_config::{DeviceConfig, OperatingMode};
+use tmag5170::health::Fault;
+use tmag5170::sensor_config::{AngleEn, MagChEn, SensorConfig};
+use tmag5170::test_config::Version;
+use tmag5170::Register;
+
+use common::setup;
+
+/// ANGLE_RESULT code of an angle in degrees
+fn angle_code(degrees: f32) -> u16 {
+    (degrees * 16.0) as u16
+}
+
+/// Field along X giving a software magnitude of 100 codes and an angle of 0 degrees
+fn compare(angle: f32, magnitude: u16) -> AngleCheck {
+    AngleCheck::compare(1600, 0, angle_code(angle), magnitude, AngleTolerance::new())
+}
+
+#[test]
+fn angles_within_the_tolerance_agree() {
+    let check = compare(2.0, 100);
+    assert!(check.angle_ok, "{:?}", check);
+    assert_eq!(check.fault(), None);
+}
+
+#[test]
+fn angle_outside_the_tolerance_is_a_mismatch() {
+    let check = compare(2.0625, 100);
+    assert!(!check.angle_ok, "{:?}", check);
+    assert!(check.magnitude_ok, "{:?}", check);
+    assert_eq!(check.fault(), Some(Fault::AngleMismatch));
+}
+
+#[test]
+fn angles_wrap_around_at_360_degrees() {
+    assert_eq!(compare(358.0, 100).fault(), None);
+    assert_eq!(compare(357.9375, 100).fault(), Some(Fault::AngleMismatch));
+
+    // Software angle just below 360 degrees
+    let tolerance = AngleTolerance::new();
+    let check = AngleCheck::compare(1600, -1, angle_code(1.5), 100, tolerance);
+    assert!(check.computed_angle > 359.9, "{:?}", check);
+    assert_eq!(check.fault(), None);
+    let check = AngleCheck::compare(1600, -1, angle_code(2.0), 100, tolerance);
+    assert_eq!(check.fault(), Some(Fault::AngleMismatch));
+}
+
+#[test]
+fn magnitude_outside_the_tolerance_is_a_mismatch() {
+    // 5% of 100 codes plus one code of rounding
+    assert_eq!(compare(0.0, 106).fault(), None);
+    assert_eq!(compare(0.0, 94).fault(), None);
+
+    for &magnitude in [107, 93].iter() {
+        let check = compare(0.0, magnitude);
+        assert!(check.angle_ok, "{:?}", check);
+        assert!(!check.magnitude_ok, "{:?}", check);
+        assert_eq!(check.fault(), Some(Fault::AngleMismatch));
+    }
+}
+
+#[test]
+fn weak_field_skips_the_angle() {
+    let tolerance = AngleTolerance::new().set_min_magnitude(101.0);
+    let check = AngleCheck::compare(1600, 0, angle_code(90.0), 100, tolerance);
+    assert!(check.angle_ok, "{:?}", check);
+    assert_eq!(check.fault(), None);
+}
+
+#[test]
+fn corrupted_angle_result_is_reported() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    let sensor = SensorConfig::new()
+        .set_mag_ch_en(MagChEn::Xyz)
+        .set_angle_en(AngleEn::Xy);
+    tmag5170.apply_sensor_config(sensor).unwrap();
+    tmag5170
+        .apply_device_config(DeviceConfig::new().set_operating_mode(OperatingMode::Standby))
+        .unwrap();
+
+    sim.set_field(10.0, 10.0, 0.0);
+    tmag5170.conv_start().unwrap();
+    sim.set_register(Register::ANGLE_RESULT, angle_code(50.0));
+
+    let check = tmag5170.check_angle(AngleTolerance::new()).unwrap();
+    assert!(!check.angle_ok, "{:?}", check);
+    assert_eq!(check.fault(), Some(Fault::AngleMismatch));
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use embedded_hal::blocking::spi::Transfer;
+
+use tmag5170::device_config::{DeviceConfig, OperatingMode};
+use tmag5170::retry::RetryPolicy;
+use tmag5170::sensor_config::{MagChEn, SensorConfig};
+use tmag5170::sim::{SimCs, SimDevice, SimSpi};
+use tmag5170::system_config::{SystemConfig, TriggerMode};
+use tmag5170::test_config::Version;
+use tmag5170::validation::{ConfigSet, ValidationMode};
+use tmag5170::{ExtError, Register, Tmag5170Array};
+
+use common::{FailingPin, PinFault};
+
+/// SPI bus wired to several simulated devices, only the selected one drives SDO
+struct SimBus {
+    devices: Vec<SimSpi>,
+}
+
+impl Transfer<u8> for SimBus {
+    type Error = Infallible;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Infallible> {
+        let request = words.to_vec();
+        words.iter_mut().for_each(|w| *w = 0xff);
+        for device in self.devices.iter_mut() {
+            let mut sdo = request.clone();
+            device.transfer(&mut sdo)?;
+            words.iter_mut().zip(sdo).for_each(|(w, b)| *w &= b);
+        }
+        Ok(words)
+    }
+}
+
+fn setup() -> ([SimDevice; 2], RefCell<SimBus>, [SimCs; 2]) {
+    let sims = [SimDevice::new(Version::A1), SimDevice::new(Version::A1)];
+    let bus = RefCell::new(SimBus {
+        devices: sims.iter().map(|sim| sim.spi()).collect(),
+    });
+    let cs = [sims[0].cs(), sims[1].cs()];
+    (sims, bus, cs)
+}
+
+fn standby_xyz(system: SystemConfig) -> ConfigSet {
+    ConfigSet {
+        device: DeviceConfig::new().set_operating_mode(OperatingMode::Standby),
+        sensor: SensorConfig::new().set_mag_ch_en(MagChEn::Xyz),
+        system,
+        ..ConfigSet::default()
+    }
+}
+
+#[test]
+fn config_is_broadcast() {
+    let (sims, bus, cs) = setup();
+    let mut sensors = Tmag5170Array::new(&bus, cs);
+    assert_eq!(sensors.len(), 2);
+
+    let config = standby_xyz(SystemConfig::new());
+    for res in sensors.apply_config(config, ValidationMode::Reject).iter() {
+        assert!(res.is_ok(), "{:?}", res);
+    }
+    for sim in sims.iter() {
+        assert_eq!(
+            sim.register(Register::DEVICE_CONFIG),
+            config.device.to_u16()
+        );
+        assert_eq!(
+            sim.register(Register::SENSOR_CONFIG),
+            config.sensor.to_u16()
+        );
+    }
+}
+
+#[test]
+fn results_are_read_per_sensor() {
+    let (sims, bus, cs) = setup();
+    let mut sensors = Tmag5170Array::new(&bus, cs);
+    let _ = sensors.apply_config(standby_xyz(SystemConfig::new()), ValidationMode::Reject);
+
+    sims[0].set_field(10.0, 0.0, 0.0);
+    sims[1].set_field(-10.0, 0.0, 0.0);
+    for res in sensors.conv_start_all().iter() {
+        assert!(res.is_ok(), "{:?}", res);
+    }
+
+    let results = sensors.read_mag_registers();
+    let x0 = results[0].unwrap().0;
+    let x1 = results[1].unwrap().0;
+    assert!(x0 > 0, "{}", x0);
+    assert_eq!(x1, -x0);
+}
+
+#[test]
+fn sync_trigger_converts_on_every_sensor() {
+    let (sims, bus, cs) = setup();
+    let mut sensors = Tmag5170Array::new(&bus, cs);
+    let system = SystemConfig::new().set_trigger_mode(TriggerMode::Cs);
+    let _ = sensors.apply_config(standby_xyz(system), ValidationMode::Reject);
+
+    sims[0].set_field(0.0, 0.0, 20.0);
+    sims[1].set_field(0.0, 0.0, 40.0);
+    for res in sensors.sync_trigger().iter() {
+        assert!(res.is_ok(), "{:?}", res);
+    }
+
+    for sim in sims.iter() {
+        assert_ne!(sim.register(Register::Z_CH_RESULT), 0);
+    }
+    assert!(sims[1].register(Register::Z_CH_RESULT) > sims[0].register(Register::Z_CH_RESULT));
+}
+
+#[test]
+fn sensors_fail_and_retry_independently() {
+    let (sims, bus, cs) = setup();
+    let mut sensors = Tmag5170Array::new(&bus, cs);
+    sensors.sensors_mut()[0].set_retry_policy(RetryPolicy::new().set_confirm(true));
+    sensors.sensors_mut()[1].set_retry_policy(RetryPolicy::new().set_max_attempts(2));
+
+    sims[0].corrupt_requests(1);
+    sims[1].corrupt_requests(1);
+    let device = DeviceConfig::new().set_operating_mode(OperatingMode::Standby);
+    let results = sensors.apply_device_config(device);
+
+    assert!(matches!(results[0], Err(ExtError::Frame(_))));
+    assert_eq!(results[1], Ok(()));
+    assert_eq!(sims[0].register(Register::DEVICE_CONFIG), 0x0000);
+    assert_eq!(sims[1].register(Register::DEVICE_CONFIG), device.to_u16());
+    assert_eq!(sensors.sensors_mut()[1].retry_counters().frame, 1);
+}
+
+#[test]
+fn unpowered_sensor_is_not_present() {
+    let (sims, bus, cs) = setup();
+    let mut sensors = Tmag5170Array::new(&bus, cs);
+
+    sims[1].set_powered(false);
+    let results = sensors.read_temp_register();
+    assert!(results[0].is_ok());
+    assert_eq!(results[1], Err(ExtError::NotPresent));
+
+    let _: [SimCs; 2] = sensors.free();
+}
+
+#[test]
+fn chip_select_errors_are_pin_errors() {
+    let (_sims, bus, _cs) = setup();
+    let mut sensors = Tmag5170Array::new(&bus, [FailingPin::new(1), FailingPin::new(u32::MAX)]);
+
+    let results = sensors.read_temp_register();
+    assert_eq!(results[0], Err(ExtError::Pin(PinFault)));
+    assert_ne!(results[1], Err(ExtError::Pin(PinFault)));
+
+    let results = sensors.sync_trigger();
+    assert_eq!(results, [Err(ExtError::Pin(PinFault)), Ok(())]);
+}
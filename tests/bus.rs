@@ -0,0 +1,132 @@
+mod common;
+
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::sync::Mutex;
+
+use embedded_hal::blocking::spi::Transfer;
+
+use tmag5170::bus::{BusMutex, SharedSpi, SharedSpiError};
+use tmag5170::device_config::{DeviceConfig, OperatingMode};
+use tmag5170::sim::{SimDevice, SimSpi};
+use tmag5170::test_config::Version;
+use tmag5170::{ExtError, Register, Tmag5170};
+
+use common::{FailingPin, PinFault};
+
+/// SPI bus wired to several simulated devices, only the selected one drives SDO
+struct SimBus {
+    devices: Vec<SimSpi>,
+}
+
+impl Transfer<u8> for SimBus {
+    type Error = Infallible;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Infallible> {
+        let request = words.to_vec();
+        words.iter_mut().for_each(|w| *w = 0xff);
+        for device in self.devices.iter_mut() {
+            let mut sdo = request.clone();
+            device.transfer(&mut sdo)?;
+            words.iter_mut().zip(sdo).for_each(|(w, b)| *w &= b);
+        }
+        Ok(words)
+    }
+}
+
+fn sims() -> (SimDevice, SimDevice, SimBus) {
+    let first = SimDevice::new(Version::A1);
+    let second = SimDevice::new(Version::A2);
+    let bus = SimBus {
+        devices: vec![first.spi(), second.spi()],
+    };
+    (first, second, bus)
+}
+
+fn standby() -> DeviceConfig {
+    DeviceConfig::new().set_operating_mode(OperatingMode::Standby)
+}
+
+#[test]
+fn released_resources_drive_a_new_driver() {
+    let sim = SimDevice::new(Version::A1);
+    let tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+
+    let (spi, cs) = tmag5170.free();
+    let mut tmag5170 = Tmag5170::new(spi, cs);
+    tmag5170.apply_device_config(standby()).unwrap();
+    assert_eq!(sim.register(Register::DEVICE_CONFIG), standby().to_u16());
+}
+
+#[test]
+fn drivers_share_a_refcell_bus() {
+    let (first, second, bus) = sims();
+    let bus = RefCell::new(bus);
+    let mut steering = Tmag5170::new_shared(&bus, first.cs());
+    let mut pedal = Tmag5170::new_shared(&bus, second.cs());
+
+    pedal.apply_device_config(standby()).unwrap();
+    assert_eq!(first.register(Register::DEVICE_CONFIG), 0x0000);
+    assert_eq!(second.register(Register::DEVICE_CONFIG), standby().to_u16());
+    assert_eq!(steering.probe(), Ok(Version::A1));
+    assert_eq!(pedal.probe(), Ok(Version::A2));
+
+    let (spi, _) = steering.free();
+    let _ = spi.free();
+}
+
+#[test]
+fn drivers_share_a_mutex_bus() {
+    let (first, second, bus) = sims();
+    let bus = Mutex::new(bus);
+    let mut steering = Tmag5170::new_shared(&bus, first.cs());
+    let mut pedal = Tmag5170::new_shared(&bus, second.cs());
+
+    steering.apply_device_config(standby()).unwrap();
+    assert_eq!(first.register(Register::DEVICE_CONFIG), standby().to_u16());
+    assert_eq!(second.register(Register::DEVICE_CONFIG), 0x0000);
+    assert_eq!(pedal.probe(), Ok(Version::A2));
+}
+
+#[test]
+fn poisoned_mutex_bus_stays_usable() {
+    let bus = Mutex::new(0u32);
+    let _ = std::panic::catch_unwind(|| {
+        BusMutex::lock(&bus, |_| {
+            panic!("driver panicked with the bus locked");
+        });
+    });
+    assert!(bus.is_poisoned());
+    assert_eq!(
+        BusMutex::lock(&bus, |count| {
+            *count += 1;
+            *count
+        }),
+        1
+    );
+}
+
+#[test]
+#[should_panic]
+fn refcell_bus_panics_on_reentrant_access() {
+    let bus = RefCell::new(0u32);
+    BusMutex::lock(&bus, |_| BusMutex::lock(&bus, |_| ()));
+}
+
+#[test]
+fn shared_chip_select_errors_are_returned() {
+    let (_first, _second, bus) = sims();
+    let bus = RefCell::new(bus);
+    let mut tmag5170 = Tmag5170::new_shared(&bus, FailingPin::new(1));
+
+    assert_eq!(
+        tmag5170.read_sys_status(),
+        Err(ExtError::Spi(SharedSpiError::Pin(PinFault)))
+    );
+
+    let mut spi = SharedSpi::new(&bus, FailingPin::new(1));
+    assert_eq!(
+        spi.transfer(&mut [0; 4]).map(|_| ()),
+        Err(SharedSpiError::Pin(PinFault))
+    );
+}
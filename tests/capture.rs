@@ -0,0 +1,106 @@
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use tmag5170::capture::{Event, Log, Recorder, ReplayError, Replayer};
+use tmag5170::device_config::{DeviceConfig, OperatingMode};
+use tmag5170::sensor_config::{MagChEn, SensorConfig};
+use tmag5170::sim::SimDevice;
+use tmag5170::test_config::Version;
+use tmag5170::{ExtError, Tmag5170};
+
+fn session<SPI, CS, E, PE>(
+    tmag5170: &mut Tmag5170<SPI, CS>,
+) -> Result<(i16, i16, i16), ExtError<E, PE>>
+where
+    SPI: Transfer<u8, Error = E>,
+    CS: OutputPin<Error = PE>,
+{
+    tmag5170.probe()?;
+    tmag5170.apply_sensor_config(SensorConfig::new().set_mag_ch_en(MagChEn::Xyz))?;
+    tmag5170.apply_device_config(DeviceConfig::new().set_operating_mode(OperatingMode::Standby))?;
+    tmag5170.conv_start()?;
+    tmag5170.read_mag_registers()
+}
+
+fn record(timestamps: bool) -> (Log, (i16, i16, i16)) {
+    let sim = SimDevice::new(Version::A1);
+    sim.set_field(10.0, -20.0, 30.0);
+    let recorder = Recorder::new().set_timestamps(timestamps);
+    let mut tmag5170 = Tmag5170::new(recorder.spi(sim.spi()), recorder.cs(sim.cs()));
+    let result = session(&mut tmag5170).unwrap();
+    (recorder.log(), result)
+}
+
+#[test]
+fn recorder_captures_frames_and_cs_edges() {
+    let (log, _) = record(false);
+
+    let records = log.records();
+    assert_eq!(records.len() % 3, 0);
+    for frame in records.chunks(3) {
+        assert_eq!(frame[0].event, Event::CsLow);
+        match &frame[1].event {
+            Event::Transfer { sdi, sdo } => assert_eq!((sdi.len(), sdo.len()), (4, 4)),
+            event => panic!("unexpected {:?}", event),
+        }
+        assert_eq!(frame[2].event, Event::CsHigh);
+        assert!(frame.iter().all(|record| record.time_us.is_none()));
+    }
+}
+
+#[test]
+fn log_bytes_round_trip() {
+    let (log, _) = record(true);
+    assert!(log.records().iter().all(|record| record.time_us.is_some()));
+
+    let bytes = log.to_bytes();
+    assert_eq!(Log::from_bytes(&bytes), Some(log));
+    assert_eq!(Log::from_bytes(&bytes[..bytes.len() - 1]), None);
+    assert_eq!(Log::from_bytes(b"nope"), None);
+}
+
+#[test]
+fn log_bytes_round_trip_long_transfer() {
+    let mut log = Log::new();
+    let sdi: Vec<u8> = (0..300).map(|idx| idx as u8).collect();
+    let sdo: Vec<u8> = sdi.iter().rev().copied().collect();
+    log.push(Event::Transfer { sdi, sdo }, Some(7));
+    log.push(
+        Event::Transfer {
+            sdi: vec![0; 128],
+            sdo: vec![1; 128],
+        },
+        None,
+    );
+
+    assert_eq!(Log::from_bytes(&log.to_bytes()), Some(log));
+}
+
+#[test]
+fn replay_reproduces_session() {
+    let (log, expected) = record(false);
+    let log = Log::from_bytes(&log.to_bytes()).unwrap();
+
+    let replayer = Replayer::new(log);
+    let mut tmag5170 = Tmag5170::new(replayer.spi(), replayer.cs());
+    assert_eq!(session(&mut tmag5170), Ok(expected));
+    assert!(replayer.is_finished());
+}
+
+#[test]
+fn replay_reports_divergence() {
+    let (log, _) = record(false);
+    let replayer = Replayer::new(log);
+    let mut tmag5170 = Tmag5170::new(replayer.spi(), replayer.cs());
+
+    tmag5170.probe().unwrap();
+    let res = tmag5170.apply_sensor_config(SensorConfig::new().set_mag_ch_en(MagChEn::X));
+    match res {
+        Err(ExtError::Spi(ReplayError {
+            expected: Some(Event::Transfer { .. }),
+            actual: Event::Transfer { .. },
+            ..
+        })) => {}
+        res => panic!("unexpected {:?}", res),
+    }
+}
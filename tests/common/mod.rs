@@ -0,0 +1,98 @@
+//! Helpers shared by the integration tests
+#![allow(dead_code)]
+
+use embedded_hal::blocking::spi::Transfer;
+use embedded_hal::digital::v2::OutputPin;
+
+use tmag5170::device_config::{DeviceConfig, OperatingMode};
+use tmag5170::sensor_config::{MagChEn, SensorConfig};
+use tmag5170::sim::{SimCs, SimDevice, SimSpi};
+use tmag5170::test_config::Version;
+use tmag5170::Tmag5170;
+
+/// Simulated device with a driver on its bus
+pub fn setup(version: Version) -> (SimDevice, Tmag5170<SimSpi, SimCs>) {
+    let sim = SimDevice::new(version);
+    let tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+    (sim, tmag5170)
+}
+
+/// Simulated A1 device converting X, Y, Z and temperature in `mode`
+pub fn setup_xyz(mode: OperatingMode) -> (SimDevice, Tmag5170<SimSpi, SimCs>) {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    tmag5170
+        .apply_sensor_config(SensorConfig::new().set_mag_ch_en(MagChEn::Xyz))
+        .unwrap();
+    let device = DeviceConfig::new().set_operating_mode(mode).set_t_en(true);
+    tmag5170.apply_device_config(device).unwrap();
+    (sim, tmag5170)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BusFault;
+
+/// SPI bus whose first `failures` transfers fail without clocking
+pub struct FlakySpi {
+    pub inner: SimSpi,
+    pub failures: u32,
+}
+
+impl FlakySpi {
+    pub fn new(inner: SimSpi, failures: u32) -> Self {
+        FlakySpi { inner, failures }
+    }
+}
+
+impl Transfer<u8> for FlakySpi {
+    type Error = BusFault;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], BusFault> {
+        if self.failures > 0 {
+            self.failures -= 1;
+            return Err(BusFault);
+        }
+        match self.inner.transfer(words) {
+            Ok(words) => Ok(words),
+            Err(never) => match never {},
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PinFault;
+
+/// Pin which fails from the `fail_at`-th level change on
+pub struct FailingPin {
+    changes: u32,
+    fail_at: u32,
+}
+
+impl FailingPin {
+    pub fn new(fail_at: u32) -> Self {
+        FailingPin {
+            changes: 0,
+            fail_at,
+        }
+    }
+
+    fn level_change(&mut self) -> Result<(), PinFault> {
+        self.changes += 1;
+        if self.changes >= self.fail_at {
+            Err(PinFault)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl OutputPin for FailingPin {
+    type Error = PinFault;
+
+    fn set_low(&mut self) -> Result<(), PinFault> {
+        self.level_change()
+    }
+
+    fn set_high(&mut self) -> Result<(), PinFault> {
+        self.level_change()
+    }
+}
@@ -0,0 +1,562 @@
+use core::fmt::Debug;
+
+use tmag5170::alert_config::{AlertConfig, AlertLatch, AlertMode, ThrxCount};
+use tmag5170::device_config::{ConvAvg, DeviceConfig, MagTempco, OperatingMode, TRate};
+use tmag5170::frame::FrameStatus;
+use tmag5170::sensor_config::{AngleEn, MagChEn, Range, SensorConfig, SleepTime};
+use tmag5170::status::{AfeStatus, ConvStatus, SysStatus};
+use tmag5170::system_config::{DataType, DiagSel, SystemConfig, TriggerMode};
+
+const BASES: [u16; 5] = [0x0000, 0xffff, 0xa5a5, 0x5a5a, 0x1234];
+
+struct Register<C> {
+    from: fn(u16) -> C,
+    to: fn(&C) -> u16,
+}
+
+/// Checks every golden vector of a field: the field bits match the datasheet value,
+/// the other bits are untouched and the getter decodes the variant
+fn check_field<C, V>(
+    register: &Register<C>,
+    mask: u16,
+    vectors: &[(V, u16)],
+    set: fn(C, V) -> C,
+    get: fn(&C) -> V,
+) where
+    C: Copy,
+    V: Copy + Debug + PartialEq,
+{
+    for &(variant, golden) in vectors {
+        assert_eq!(golden & !mask, 0, "{:?} outside of the field", variant);
+        for &base in BASES.iter() {
+            let config = set((register.from)(base), variant);
+            let value = (register.to)(&config);
+            assert_eq!(value & mask, golden, "{:?} on {:#06x}", variant, base);
+            assert_eq!(
+                value & !mask,
+                base & !mask,
+                "{:?} on {:#06x}",
+                variant,
+                base
+            );
+            assert_eq!(get(&config), variant);
+        }
+    }
+}
+
+fn check_round_trip<C>(register: &Register<C>) {
+    for value in 0..=0xffff {
+        assert_eq!((register.to)(&(register.from)(value)), value);
+    }
+}
+
+fn bool_vectors(bit: u16) -> [(bool, u16); 2] {
+    [(false, 0x0000), (true, 0b1 << bit)]
+}
+
+const DEVICE: Register<DeviceConfig> = Register {
+    from: DeviceConfig::form_u16,
+    to: DeviceConfig::to_u16,
+};
+
+const SENSOR: Register<SensorConfig> = Register {
+    from: SensorConfig::form_u16,
+    to: SensorConfig::to_u16,
+};
+
+const SYSTEM: Register<SystemConfig> = Register {
+    from: SystemConfig::form_u16,
+    to: SystemConfig::to_u16,
+};
+
+const ALERT: Register<AlertConfig> = Register {
+    from: AlertConfig::form_u16,
+    to: AlertConfig::to_u16,
+};
+
+#[test]
+fn device_config_fields() {
+    check_field(
+        &DEVICE,
+        0x7000,
+        &[
+            (ConvAvg::Avg1x, 0x0000),
+            (ConvAvg::Avg2x, 0x1000),
+            (ConvAvg::Avg4x, 0x2000),
+            (ConvAvg::Avg8x, 0x3000),
+            (ConvAvg::Avg16x, 0x4000),
+            (ConvAvg::Avg32x, 0x5000),
+        ],
+        DeviceConfig::set_conv_avg,
+        |c| c.conv_avg().unwrap(),
+    );
+    check_field(
+        &DEVICE,
+        0x0300,
+        &[
+            (MagTempco::CurrentSensor, 0x0000),
+            (MagTempco::NdBFe, 0x0100),
+            (MagTempco::Ceramic, 0x0300),
+        ],
+        DeviceConfig::set_mag_tempco,
+        |c| c.mag_tempco().unwrap(),
+    );
+    check_field(
+        &DEVICE,
+        0x0070,
+        &[
+            (OperatingMode::Configuration, 0x0000),
+            (OperatingMode::Standby, 0x0010),
+            (OperatingMode::Active, 0x0020),
+            (OperatingMode::ActiveTrigger, 0x0030),
+            (OperatingMode::WakeupAndSleep, 0x0040),
+            (OperatingMode::Sleep, 0x0050),
+            (OperatingMode::DeepSleep, 0x0060),
+        ],
+        DeviceConfig::set_operating_mode,
+        |c| c.operating_mode().unwrap(),
+    );
+    check_field(
+        &DEVICE,
+        0x0008,
+        &bool_vectors(3),
+        DeviceConfig::set_t_en,
+        DeviceConfig::t_en,
+    );
+    check_field(
+        &DEVICE,
+        0x0004,
+        &[(TRate::SameRate, 0x0000), (TRate::OncePerConvSet, 0x0004)],
+        DeviceConfig::set_t_rate,
+        DeviceConfig::t_rate,
+    );
+    check_field(
+        &DEVICE,
+        0x0002,
+        &bool_vectors(1),
+        DeviceConfig::set_t_limit_check_en,
+        DeviceConfig::t_limit_check_en,
+    );
+    check_field(
+        &DEVICE,
+        0x0001,
+        &bool_vectors(0),
+        DeviceConfig::set_t_comp_en,
+        DeviceConfig::t_comp_en,
+    );
+}
+
+#[test]
+fn device_config_layout() {
+    let fields = [0x7000, 0x0300, 0x0070, 0x0008, 0x0004, 0x0002, 0x0001];
+    check_layout(&fields, DeviceConfig::RESERVED_BITS);
+    assert_eq!(DeviceConfig::new().to_u16(), 0x0000);
+    check_round_trip(&DEVICE);
+}
+
+#[test]
+fn sensor_config_fields() {
+    check_field(
+        &SENSOR,
+        0xc000,
+        &[
+            (AngleEn::NoAngle, 0x0000),
+            (AngleEn::Xy, 0x4000),
+            (AngleEn::Yz, 0x8000),
+            (AngleEn::Zx, 0xc000),
+        ],
+        SensorConfig::set_angle_en,
+        SensorConfig::angle_en,
+    );
+    check_field(
+        &SENSOR,
+        0x3c00,
+        &[
+            (SleepTime::Ms1, 0x0000),
+            (SleepTime::Ms5, 0x0400),
+            (SleepTime::Ms10, 0x0800),
+            (SleepTime::Ms15, 0x0c00),
+            (SleepTime::Ms20, 0x1000),
+            (SleepTime::Ms30, 0x1400),
+            (SleepTime::Ms50, 0x1800),
+            (SleepTime::Ms100, 0x1c00),
+            (SleepTime::Ms500, 0x2000),
+            (SleepTime::Ms1000, 0x2400),
+        ],
+        SensorConfig::set_sleep_time,
+        |c| c.sleep_time().unwrap(),
+    );
+    check_field(
+        &SENSOR,
+        0x03c0,
+        &[
+            (MagChEn::Off, 0x0000),
+            (MagChEn::X, 0x0040),
+            (MagChEn::Y, 0x0080),
+            (MagChEn::Xy, 0x00c0),
+            (MagChEn::Z, 0x0100),
+            (MagChEn::Zx, 0x0140),
+            (MagChEn::Yz, 0x0180),
+            (MagChEn::Xyz, 0x01c0),
+            (MagChEn::Xyx, 0x0200),
+            (MagChEn::Yxy, 0x0240),
+            (MagChEn::Yzy, 0x0280),
+            (MagChEn::Zyz, 0x02c0),
+            (MagChEn::Zxz, 0x0300),
+            (MagChEn::Xzx, 0x0340),
+            (MagChEn::Xyzyx, 0x0380),
+            (MagChEn::Xyzzyx, 0x03c0),
+        ],
+        SensorConfig::set_mag_ch_en,
+        SensorConfig::mag_ch_en,
+    );
+    check_field(
+        &SENSOR,
+        0x0030,
+        &[
+            (Range::A1_50mT_A2_200mT, 0x0000),
+            (Range::A1_25mT_A2_133mT, 0x0010),
+            (Range::A1_100mT_A2_300mT, 0x0020),
+        ],
+        SensorConfig::set_z_range,
+        |c| c.z_range().unwrap(),
+    );
+    check_field(
+        &SENSOR,
+        0x000c,
+        &[
+            (Range::A1_50mT_A2_200mT, 0x0000),
+            (Range::A1_25mT_A2_133mT, 0x0004),
+            (Range::A1_100mT_A2_300mT, 0x0008),
+        ],
+        SensorConfig::set_y_range,
+        |c| c.y_range().unwrap(),
+    );
+    check_field(
+        &SENSOR,
+        0x0003,
+        &[
+            (Range::A1_50mT_A2_200mT, 0x0000),
+            (Range::A1_25mT_A2_133mT, 0x0001),
+            (Range::A1_100mT_A2_300mT, 0x0002),
+        ],
+        SensorConfig::set_x_range,
+        |c| c.x_range().unwrap(),
+    );
+}
+
+#[test]
+fn sensor_config_layout() {
+    let fields = [0xc000, 0x3c00, 0x03c0, 0x0030, 0x000c, 0x0003];
+    check_layout(&fields, SensorConfig::RESERVED_BITS);
+    assert_eq!(SensorConfig::new().to_u16(), 0x0000);
+    check_round_trip(&SENSOR);
+}
+
+#[test]
+fn sensor_config_reserved_codes() {
+    assert_eq!(SensorConfig::form_u16(0x2800).sleep_time(), None);
+    assert_eq!(SensorConfig::form_u16(0x0030).z_range(), None);
+    assert_eq!(SensorConfig::form_u16(0x000c).y_range(), None);
+    assert_eq!(SensorConfig::form_u16(0x0003).x_range(), None);
+}
+
+#[test]
+fn system_config_fields() {
+    check_field(
+        &SYSTEM,
+        0x3000,
+        &[
+            (DiagSel::AllData, 0x0000),
+            (DiagSel::EnabledData, 0x1000),
+            (DiagSel::AllDataInSeq, 0x2000),
+            (DiagSel::EnabledDataInSeq, 0x3000),
+        ],
+        SystemConfig::set_diag_sel,
+        SystemConfig::diag_sel,
+    );
+    check_field(
+        &SYSTEM,
+        0x0600,
+        &[
+            (TriggerMode::Spi, 0x0000),
+            (TriggerMode::Cs, 0x0200),
+            (TriggerMode::Alert, 0x0400),
+        ],
+        SystemConfig::set_trigger_mode,
+        |c| c.trigger_mode().unwrap(),
+    );
+    check_field(
+        &SYSTEM,
+        0x01c0,
+        &[
+            (DataType::Default, 0x0000),
+            (DataType::XY, 0x0040),
+            (DataType::XZ, 0x0080),
+            (DataType::ZY, 0x00c0),
+            (DataType::XT, 0x0100),
+            (DataType::YT, 0x0140),
+            (DataType::ZT, 0x0180),
+            (DataType::AM, 0x01c0),
+        ],
+        SystemConfig::set_data_type,
+        SystemConfig::data_type,
+    );
+    check_field(
+        &SYSTEM,
+        0x0020,
+        &bool_vectors(5),
+        SystemConfig::set_diag_en,
+        SystemConfig::diag_en,
+    );
+    check_field(
+        &SYSTEM,
+        0x0004,
+        &bool_vectors(2),
+        SystemConfig::set_t_z_limit_check,
+        SystemConfig::t_z_limit_check,
+    );
+    check_field(
+        &SYSTEM,
+        0x0002,
+        &bool_vectors(1),
+        SystemConfig::set_t_y_limit_check,
+        SystemConfig::t_y_limit_check,
+    );
+    check_field(
+        &SYSTEM,
+        0x0001,
+        &bool_vectors(0),
+        SystemConfig::set_t_x_limit_check,
+        SystemConfig::t_x_limit_check,
+    );
+}
+
+#[test]
+fn system_config_layout() {
+    let fields = [0x3000, 0x0600, 0x01c0, 0x0020, 0x0004, 0x0002, 0x0001];
+    check_layout(&fields, SystemConfig::RESERVED_BITS);
+    assert_eq!(SystemConfig::new().to_u16(), 0x0000);
+    assert_eq!(SystemConfig::form_u16(0x0600).trigger_mode(), None);
+    check_round_trip(&SYSTEM);
+}
+
+#[test]
+fn alert_config_fields() {
+    check_field(
+        &ALERT,
+        0x2000,
+        &[
+            (AlertLatch::NotLatched, 0x0000),
+            (AlertLatch::Latched, 0x2000),
+        ],
+        AlertConfig::set_alert_latch,
+        AlertConfig::alert_latch,
+    );
+    check_field(
+        &ALERT,
+        0x1000,
+        &[
+            (AlertMode::Interrupt, 0x0000),
+            (AlertMode::Comparator, 0x1000),
+        ],
+        AlertConfig::set_alert_mode,
+        AlertConfig::alert_mode,
+    );
+    check_field(
+        &ALERT,
+        0x0800,
+        &bool_vectors(11),
+        AlertConfig::set_status_alrt_enable,
+        AlertConfig::status_alrt_enable,
+    );
+    check_field(
+        &ALERT,
+        0x0100,
+        &bool_vectors(8),
+        AlertConfig::set_rslt_alrt_enable,
+        AlertConfig::rslt_alrt_enable,
+    );
+    check_field(
+        &ALERT,
+        0x0030,
+        &[
+            (ThrxCount::ConvResult1, 0x0000),
+            (ThrxCount::ConvResult2, 0x0010),
+            (ThrxCount::ConvResult3, 0x0020),
+            (ThrxCount::ConvResult4, 0x0030),
+        ],
+        AlertConfig::set_thrx_count,
+        AlertConfig::thrx_count,
+    );
+    check_field(
+        &ALERT,
+        0x0008,
+        &bool_vectors(3),
+        AlertConfig::set_t_thrx_alrt_enable,
+        AlertConfig::t_thrx_alrt_enable,
+    );
+    check_field(
+        &ALERT,
+        0x0004,
+        &bool_vectors(2),
+        AlertConfig::set_z_thrx_alrt_enable,
+        AlertConfig::z_thrx_alrt_enable,
+    );
+    check_field(
+        &ALERT,
+        0x0002,
+        &bool_vectors(1),
+        AlertConfig::set_y_thrx_alrt_enable,
+        AlertConfig::y_thrx_alrt_enable,
+    );
+    check_field(
+        &ALERT,
+        0x0001,
+        &bool_vectors(0),
+        AlertConfig::set_x_thrx_alrt_enable,
+        AlertConfig::x_thrx_alrt_enable,
+    );
+}
+
+#[test]
+fn alert_config_layout() {
+    let fields = [
+        0x2000, 0x1000, 0x0800, 0x0100, 0x0030, 0x0008, 0x0004, 0x0002, 0x0001,
+    ];
+    check_layout(&fields, AlertConfig::RESERVED_BITS);
+    assert_eq!(AlertConfig::new().to_u16(), 0x0000);
+    check_round_trip(&ALERT);
+}
+
+#[test]
+fn device_config_reserved_codes() {
+    assert_eq!(DeviceConfig::form_u16(0x6000).conv_avg(), None);
+    assert_eq!(DeviceConfig::form_u16(0x0200).mag_tempco(), None);
+    assert_eq!(DeviceConfig::form_u16(0x0070).operating_mode(), None);
+}
+
+/// Fields and reserved bits cover the register exactly once
+fn check_layout(fields: &[u16], reserved: u16) {
+    let mut covered = reserved;
+    for &mask in fields {
+        assert_eq!(covered & mask, 0, "field {:#06x} overlaps", mask);
+        covered |= mask;
+    }
+    assert_eq!(covered, 0xffff);
+}
+
+const CONV: Register<ConvStatus> = Register {
+    from: ConvStatus::from_u16,
+    to: ConvStatus::to_u16,
+};
+
+const AFE: Register<AfeStatus> = Register {
+    from: AfeStatus::from_u16,
+    to: AfeStatus::to_u16,
+};
+
+const SYS: Register<SysStatus> = Register {
+    from: SysStatus::from_u16,
+    to: SysStatus::to_u16,
+};
+
+type Flag<C> = (u16, fn(C, bool) -> C, fn(&C) -> bool);
+
+fn check_flags<C: Copy>(register: &Register<C>, flags: &[Flag<C>]) {
+    for &(bit, set, get) in flags {
+        check_field(register, 0b1 << bit, &bool_vectors(bit), set, get);
+    }
+}
+
+#[test]
+fn status_fields() {
+    check_flags(
+        &CONV,
+        &[
+            (13, ConvStatus::set_rdy, ConvStatus::rdy),
+            (12, ConvStatus::set_a, ConvStatus::a),
+            (11, ConvStatus::set_t, ConvStatus::t),
+            (10, ConvStatus::set_z, ConvStatus::z),
+            (9, ConvStatus::set_y, ConvStatus::y),
+            (8, ConvStatus::set_x, ConvStatus::x),
+        ],
+    );
+    check_field(
+        &CONV,
+        0x0070,
+        &[(0, 0x0000), (5, 0x0050), (7, 0x0070)],
+        ConvStatus::set_set_count,
+        ConvStatus::set_count,
+    );
+    check_field(
+        &CONV,
+        0x0003,
+        &[(0, 0x0000), (2, 0x0002), (3, 0x0003)],
+        ConvStatus::set_alrt_status,
+        ConvStatus::alrt_status,
+    );
+
+    check_flags(
+        &AFE,
+        &[
+            (15, AfeStatus::set_cfg_reset, AfeStatus::cfg_reset),
+            (12, AfeStatus::set_sens_stat, AfeStatus::sens_stat),
+            (11, AfeStatus::set_temp_stat, AfeStatus::temp_stat),
+            (10, AfeStatus::set_zhs_stat, AfeStatus::zhs_stat),
+            (9, AfeStatus::set_yhs_stat, AfeStatus::yhs_stat),
+            (8, AfeStatus::set_xhs_stat, AfeStatus::xhs_stat),
+            (1, AfeStatus::set_trim_stat, AfeStatus::trim_stat),
+            (0, AfeStatus::set_ldo_stat, AfeStatus::ldo_stat),
+        ],
+    );
+
+    check_flags(
+        &SYS,
+        &[
+            (15, SysStatus::set_alrt_lvl, SysStatus::alrt_lvl),
+            (14, SysStatus::set_alrt_drv, SysStatus::alrt_drv),
+            (13, SysStatus::set_sdo_drv, SysStatus::sdo_drv),
+            (12, SysStatus::set_crc_stat, SysStatus::crc_stat),
+            (11, SysStatus::set_frame_stat, SysStatus::frame_stat),
+            (5, SysStatus::set_vcc_ov, SysStatus::vcc_ov),
+            (4, SysStatus::set_vcc_uv, SysStatus::vcc_uv),
+            (3, SysStatus::set_temp_thx, SysStatus::temp_thx),
+            (2, SysStatus::set_zch_thx, SysStatus::zch_thx),
+            (1, SysStatus::set_ych_thx, SysStatus::ych_thx),
+            (0, SysStatus::set_xch_thx, SysStatus::xch_thx),
+        ],
+    );
+    check_field(
+        &SYS,
+        0x0700,
+        &[(0, 0x0000), (2, 0x0200), (7, 0x0700)],
+        SysStatus::set_operating_stat,
+        SysStatus::operating_stat,
+    );
+}
+
+#[test]
+fn frame_status_fields() {
+    let flags: [Flag<FrameStatus>; 8] = [
+        (
+            11,
+            FrameStatus::set_prev_crc_error,
+            FrameStatus::prev_crc_error,
+        ),
+        (10, FrameStatus::set_cfg_reset, FrameStatus::cfg_reset),
+        (9, FrameStatus::set_sys_alert, FrameStatus::sys_alert),
+        (8, FrameStatus::set_afe_alert, FrameStatus::afe_alert),
+        (7, FrameStatus::set_x_stat, FrameStatus::x_stat),
+        (6, FrameStatus::set_y_stat, FrameStatus::y_stat),
+        (5, FrameStatus::set_z_stat, FrameStatus::z_stat),
+        (4, FrameStatus::set_t_stat, FrameStatus::t_stat),
+    ];
+    for &(bit, set, get) in flags.iter() {
+        let status = set(FrameStatus::from_u16(0x0fff), false);
+        assert_eq!(status.to_u16(), 0x0fff & !(0b1 << bit));
+        let status = set(status, true);
+        assert_eq!(status.to_u16(), 0x0fff);
+        assert!(get(&status));
+    }
+}
@@ -0,0 +1,125 @@
+use tmag5170::capture::{Event, Recorder};
+use tmag5170::decode::{parse_csv, CsvError, Decoder, Response};
+use tmag5170::device_config::{ConvAvg, DeviceConfig, OperatingMode};
+use tmag5170::sim::SimDevice;
+use tmag5170::system_config::{DataType, SystemConfig};
+use tmag5170::test_config::Version;
+use tmag5170::{Register, Tmag5170};
+
+/// Captures a session on the simulator and exports it like Saleae Logic 1
+fn logic1_csv() -> String {
+    let sim = SimDevice::new(Version::A1);
+    let recorder = Recorder::new();
+    let mut tmag5170 = Tmag5170::new(recorder.spi(sim.spi()), recorder.cs(sim.cs()));
+    let device = DeviceConfig::new()
+        .set_conv_avg(ConvAvg::Avg32x)
+        .set_operating_mode(OperatingMode::Standby);
+    tmag5170.apply_device_config(device).unwrap();
+    tmag5170.conv_start().unwrap();
+    tmag5170
+        .apply_system_config(SystemConfig::new().set_data_type(DataType::AM))
+        .unwrap();
+    tmag5170.read_am().unwrap();
+
+    let mut csv = String::from("Time [s],Packet ID,MOSI,MISO\n");
+    let mut packet = 0;
+    for record in recorder.log().records() {
+        match &record.event {
+            Event::Transfer { sdi, sdo } => {
+                for (i, (mosi, miso)) in sdi.iter().zip(sdo).enumerate() {
+                    csv += &format!(
+                        "0.{:03}{},{},0x{:02X},0x{:02X}\n",
+                        packet, i, packet, mosi, miso
+                    );
+                }
+            }
+            Event::CsHigh => packet += 1,
+            Event::CsLow => (),
+        }
+    }
+    csv
+}
+
+#[test]
+fn decodes_logic1_export() {
+    let capture = parse_csv(&logic1_csv()).unwrap();
+    assert_eq!(capture.frames.len(), 4);
+    assert_eq!(capture.dropped_bytes, 0);
+    assert_eq!(capture.frames[1].time.as_deref(), Some("0.0010"));
+
+    let mut decoder = Decoder::new();
+    let frames: Vec<_> = capture
+        .frames
+        .iter()
+        .map(|f| decoder.decode(&f.sdi, &f.sdo))
+        .collect();
+
+    assert!(!frames[0].read);
+    assert_eq!(frames[0].register, Some(Register::DEVICE_CONFIG));
+    assert_eq!(frames[0].value, 0x5010);
+    assert!(frames[0].sdi_crc_ok && frames[0].sdo_crc_ok);
+    let line = frames[0].to_string();
+    assert!(
+        line.starts_with("W DEVICE_CONFIG = 0x5010 [conv_avg=Avg32x"),
+        "{}",
+        line
+    );
+    assert!(line.contains("operating_mode=Standby"), "{}", line);
+    assert!(line.contains("[cfg_reset]"), "{}", line);
+
+    assert!(frames[1].read);
+    assert_eq!(frames[1].register, Some(Register::DEVICE_CONFIG));
+    assert_eq!(frames[1].cmd, 0x1);
+    assert!(frames[1].to_string().contains("cmd=0x1 (conv start)"));
+
+    assert!(!frames[2].read);
+    assert_eq!(frames[2].register, Some(Register::SYSTEM_CONFIG));
+
+    match frames[3].response {
+        Response::Special { data_type, .. } => assert_eq!(data_type, DataType::AM),
+        response => panic!("unexpected {:?}", response),
+    }
+    assert!(frames[3].sdo_crc_ok);
+}
+
+#[test]
+fn decodes_logic2_export_and_drops_partial_frames() {
+    let csv = "\
+name,type,start_time,duration,mosi,miso
+SPI,enable,0.1,0,,
+SPI,result,0.1,1e-6,0x8E,0x00
+SPI,result,0.1,1e-6,0x00,0x00
+SPI,disable,0.1,0,,
+SPI,enable,0.2,0,,
+SPI,result,0.2,1e-6,0x8E,0x40
+SPI,result,0.2,1e-6,0x00,0x00
+SPI,result,0.2,1e-6,0x00,0x00
+SPI,result,0.2,1e-6,0x0B,0x00
+SPI,disable,0.2,0,,
+";
+    let capture = parse_csv(csv).unwrap();
+    assert_eq!(capture.dropped_bytes, 2);
+    assert_eq!(capture.frames.len(), 1);
+    assert_eq!(capture.frames[0].time.as_deref(), Some("0.2"));
+    assert_eq!(capture.frames[0].sdi, [0x8e, 0x00, 0x00, 0x0b]);
+
+    let frame = Decoder::new().decode(&capture.frames[0].sdi, &capture.frames[0].sdo);
+    assert!(frame.read);
+    assert_eq!(frame.register, Some(Register::SYS_STATUS));
+    assert!(frame.to_string().contains("[cfg_reset]"));
+}
+
+#[test]
+fn reports_bad_crc_and_csv_errors() {
+    let mut decoder = Decoder::new();
+    let frame = decoder.decode(&[0x8e, 0x00, 0x00, 0x0a], &[0x00, 0x00, 0x00, 0x01]);
+    assert!(!frame.sdi_crc_ok);
+    assert!(!frame.sdo_crc_ok);
+    assert!(frame.to_string().contains("crc=BAD"));
+
+    assert_eq!(
+        parse_csv("time,mosi\n"),
+        Err(CsvError::MissingColumn("MISO"))
+    );
+    assert_eq!(parse_csv("mosi,miso\n0x1,zz\n"), Err(CsvError::Value(2)));
+}
@@ -0,0 +1,124 @@
+use tmag5170::device_config::{ConvAvg, DeviceConfig, OperatingMode};
+use tmag5170::dump::{ParseError, RegisterDump};
+use tmag5170::sensor_config::{AngleEn, MagChEn, SensorConfig};
+use tmag5170::sim::SimDevice;
+use tmag5170::system_config::{DataType, SystemConfig};
+use tmag5170::test_config::Version;
+use tmag5170::{ExtError, Register, Tmag5170};
+
+fn configured_dump() -> RegisterDump {
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+    tmag5170
+        .apply_sensor_config(
+            SensorConfig::new()
+                .set_mag_ch_en(MagChEn::Xyz)
+                .set_angle_en(AngleEn::Xy),
+        )
+        .unwrap();
+    tmag5170
+        .apply_device_config(
+            DeviceConfig::new()
+                .set_conv_avg(ConvAvg::Avg8x)
+                .set_operating_mode(OperatingMode::Standby),
+        )
+        .unwrap();
+    sim.set_register(Register::X_THRX_CONFIG, 0x1234);
+    sim.set_field(10.0, 0.0, 0.0);
+    tmag5170.conv_start().unwrap();
+    tmag5170.dump_registers().unwrap()
+}
+
+#[test]
+fn dump_reads_every_register() {
+    let dump = configured_dump();
+
+    assert_eq!(dump.iter().count(), Register::ALL.len());
+    assert_eq!(dump.sensor_config().mag_ch_en(), MagChEn::Xyz);
+    assert_eq!(dump.device_config().conv_avg(), Some(ConvAvg::Avg8x));
+    assert_eq!(dump.get(Register::X_THRX_CONFIG), 0x1234);
+    assert_eq!(dump.get(Register::X_CH_RESULT), 6554);
+    assert_eq!(dump.test_config().ver(), Some(Version::A1));
+    assert!(dump.afe_status().cfg_reset());
+}
+
+#[test]
+fn text_round_trip() {
+    let dump = configured_dump();
+    let text = dump.to_text();
+
+    assert!(text.contains("DEVICE_CONFIG    = 0x3010  # [conv_avg=Avg8x"));
+    assert_eq!(RegisterDump::from_text(&text), Ok(dump));
+}
+
+#[test]
+fn text_errors() {
+    let text = configured_dump().to_text();
+
+    let missing: String = text
+        .lines()
+        .filter(|l| !l.starts_with("OSC_MONITOR"))
+        .map(|l| format!("{}\n", l))
+        .collect();
+    assert_eq!(
+        RegisterDump::from_text(&missing),
+        Err(ParseError::Missing(Register::OSC_MONITOR))
+    );
+
+    let duplicate = format!("{}sensor_config = 0\n", text);
+    assert_eq!(
+        RegisterDump::from_text(&duplicate),
+        Err(ParseError::Duplicate(Register::SENSOR_CONFIG))
+    );
+
+    let invalid = format!("{}DEVICE_CONFIG = 0x10000\n", text);
+    let line = invalid.lines().count();
+    assert_eq!(
+        RegisterDump::from_text(&invalid),
+        Err(ParseError::Line(line))
+    );
+}
+
+#[test]
+fn restore_writes_writable_bits_only() {
+    let dump = configured_dump();
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+    tmag5170.set_write_verify(true);
+
+    let with_reserved = dump.set(
+        Register::DEVICE_CONFIG,
+        dump.get(Register::DEVICE_CONFIG) | DeviceConfig::RESERVED_BITS,
+    );
+    tmag5170.restore_registers(&with_reserved).unwrap();
+
+    let restored = tmag5170.dump_registers().unwrap();
+    assert_eq!(restored.diff(&dump).count(), 0);
+    assert_eq!(sim.register(Register::DEVICE_CONFIG), 0x3010);
+    assert_eq!(tmag5170.config().sensor, dump.sensor_config());
+    assert_eq!(restored.get(Register::X_CH_RESULT), 0);
+}
+
+#[test]
+fn restore_leaves_test_config_alone() {
+    let dump = configured_dump().set(Register::TEST_CONFIG, 0x0006);
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+
+    tmag5170.restore_registers(&dump).unwrap();
+    assert_eq!(sim.register(Register::TEST_CONFIG) & 0x0007, 0x0000);
+
+    tmag5170.restore_test_config(&dump).unwrap();
+    assert_eq!(sim.register(Register::TEST_CONFIG) & 0x0007, 0x0006);
+}
+
+#[test]
+fn dump_needs_default_data_type() {
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+    tmag5170
+        .apply_system_config(SystemConfig::new().set_data_type(DataType::AM))
+        .unwrap();
+
+    assert_eq!(tmag5170.dump_registers(), Err(ExtError::SpecialDataType));
+}
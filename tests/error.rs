@@ -0,0 +1,93 @@
+mod common;
+
+use tmag5170::frame::FrameStatus;
+use tmag5170::sim::SimDevice;
+use tmag5170::test_config::Version;
+use tmag5170::validation::ConfigIssue;
+use tmag5170::{ExtError, Register, Tmag5170};
+
+use common::{BusFault, FailingPin, FlakySpi, PinFault};
+
+#[test]
+fn spi_error_is_returned() {
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(FlakySpi::new(sim.spi(), u32::MAX), sim.cs());
+
+    assert_eq!(tmag5170.read_sys_status(), Err(ExtError::Spi(BusFault)));
+}
+
+#[test]
+fn chip_select_errors_are_returned() {
+    let sim = SimDevice::new(Version::A1);
+    let cs = FailingPin::new(1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), cs);
+    assert_eq!(tmag5170.read_sys_status(), Err(ExtError::Pin(PinFault)));
+
+    // The frame is clocked, but NCS fails to rise
+    let cs = FailingPin::new(2);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), cs);
+    assert_eq!(tmag5170.read_sys_status(), Err(ExtError::Pin(PinFault)));
+}
+
+#[test]
+fn errors_are_displayed() {
+    type Error = ExtError<BusFault, PinFault>;
+    let cases: [(Error, &str); 11] = [
+        (ExtError::Spi(BusFault), "SPI error: BusFault"),
+        (ExtError::Pin(PinFault), "chip select pin error: PinFault"),
+        (ExtError::Crc, "CRC mismatch in SDO frame"),
+        (
+            ExtError::Frame(FrameStatus::from_u16(0x800)),
+            "device rejected previous frame (status 0x800)",
+        ),
+        (ExtError::Timeout, "operation timed out"),
+        (
+            ExtError::InvalidConfig(ConfigIssue::AmWithoutAngle),
+            "invalid configuration: AmWithoutAngle",
+        ),
+        (ExtError::AngleDisabled, "angle calculation is disabled"),
+        (
+            ExtError::SpecialDataType,
+            "register reads need DATA_TYPE Default",
+        ),
+        (ExtError::NotPresent, "device not present"),
+        (
+            ExtError::WrongDevice {
+                register: Register::TEST_CONFIG,
+                value: 0x0040,
+            },
+            "unexpected device, TEST_CONFIG reads 0x0040",
+        ),
+        (
+            ExtError::Mismatch {
+                register: Register::DEVICE_CONFIG,
+                expected: 0x0010,
+                actual: 0x0000,
+                bits: 0x0010,
+            },
+            "DEVICE_CONFIG read back 0x0000 instead of 0x0010 (bits 0x0010)",
+        ),
+    ];
+
+    for (error, text) in cases.iter() {
+        assert_eq!(error.to_string(), *text);
+    }
+}
+
+#[test]
+fn errors_are_std_errors() {
+    let error: Box<dyn std::error::Error> = Box::new(ExtError::<BusFault, PinFault>::Crc);
+    assert_eq!(error.to_string(), "CRC mismatch in SDO frame");
+}
+
+#[cfg(feature = "embedded-hal-1")]
+#[test]
+fn errors_map_to_spi_error_kinds() {
+    use embedded_hal_1::spi::{Error, ErrorKind};
+
+    let overrun: ExtError<ErrorKind, PinFault> = ExtError::Spi(ErrorKind::Overrun);
+    assert_eq!(overrun.kind(), ErrorKind::Overrun);
+
+    let crc: ExtError<ErrorKind, PinFault> = ExtError::Crc;
+    assert_eq!(crc.kind(), ErrorKind::Other);
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use tmag5170::health::{Fault, Faults, HealthMonitor, HealthState};
+use tmag5170::sim::SimDevice;
+use tmag5170::test_config::Version;
+use tmag5170::{ExtError, Register, Tmag5170};
+
+use common::{BusFault, FlakySpi};
+
+fn faults(list: &[Fault]) -> Faults {
+    let mut faults = Faults::new();
+    for &fault in list {
+        faults.insert(fault);
+    }
+    faults
+}
+
+#[test]
+fn critical_faults_are_latched() {
+    let mut monitor = HealthMonitor::new();
+    assert_eq!(monitor.update(0, Faults::new()), HealthState::Ok);
+
+    let ldo = faults(&[Fault::Ldo]);
+    assert_eq!(monitor.update(10, ldo), HealthState::Faulted(ldo));
+    assert_eq!(monitor.update(20, Faults::new()), HealthState::Faulted(ldo));
+
+    monitor.clear_latched();
+    assert_eq!(monitor.state(), HealthState::Ok);
+    assert_eq!(monitor.count(Fault::Ldo), 1);
+}
+
+#[test]
+fn non_critical_faults_follow_the_latest_sample() {
+    let mut monitor = HealthMonitor::new();
+
+    let crc = faults(&[Fault::SpiCrc]);
+    assert_eq!(monitor.update(0, crc), HealthState::Degraded(crc));
+    assert_eq!(monitor.update(1, Faults::new()), HealthState::Ok);
+    assert!(monitor.latched().contains(Fault::SpiCrc));
+}
+
+#[test]
+fn faults_are_counted_with_their_first_occurrence() {
+    let mut monitor = HealthMonitor::new();
+    monitor.update(5, faults(&[Fault::SpiCrc]));
+    monitor.update(7, faults(&[Fault::SpiCrc, Fault::VccUndervoltage]));
+    monitor.clear_latched();
+    monitor.update(9, faults(&[Fault::SpiCrc]));
+
+    assert_eq!(monitor.count(Fault::SpiCrc), 3);
+    assert_eq!(monitor.first_occurrence(Fault::SpiCrc), Some(5));
+    assert_eq!(monitor.count(Fault::VccUndervoltage), 1);
+    assert_eq!(monitor.first_occurrence(Fault::VccUndervoltage), Some(7));
+    assert_eq!(monitor.count(Fault::Trim), 0);
+    assert_eq!(monitor.first_occurrence(Fault::Trim), None);
+}
+
+#[test]
+fn power_up_reset_is_expected() {
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+    let mut monitor = HealthMonitor::new();
+
+    assert_eq!(tmag5170.check_health(&mut monitor, 0), Ok(HealthState::Ok));
+    assert_eq!(monitor.count(Fault::ConfigReset), 0);
+
+    sim.power_on_reset();
+    let reset = faults(&[Fault::ConfigReset]);
+    assert_eq!(
+        tmag5170.check_health(&mut monitor, 1),
+        Ok(HealthState::Faulted(reset))
+    );
+    assert_eq!(monitor.first_occurrence(Fault::ConfigReset), Some(1));
+}
+
+#[test]
+fn power_up_reset_is_expected_after_a_failed_read() {
+    let sim = SimDevice::new(Version::A1);
+    let spi = FlakySpi::new(sim.spi(), 1);
+    let mut tmag5170 = Tmag5170::new(spi, sim.cs());
+    let mut monitor = HealthMonitor::new();
+
+    assert_eq!(
+        tmag5170.check_health(&mut monitor, 0),
+        Err(ExtError::Spi(BusFault))
+    );
+    assert_eq!(tmag5170.check_health(&mut monitor, 1), Ok(HealthState::Ok));
+    assert_eq!(monitor.count(Fault::ConfigReset), 0);
+    assert_eq!(monitor.count(Fault::Communication), 1);
+}
+
+#[test]
+fn conversion_alert_is_reported() {
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+    let mut monitor = HealthMonitor::new();
+    tmag5170.check_health(&mut monitor, 0).unwrap();
+
+    sim.set_register(Register::CONV_STATUS, 0b01);
+    let alert = faults(&[Fault::ConversionAlert]);
+    assert_eq!(
+        tmag5170.check_health(&mut monitor, 1),
+        Ok(HealthState::Degraded(alert))
+    );
+}
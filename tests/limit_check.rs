@@ -0,0 +1,124 @@
+mod common;
+
+use tmag5170::device_config::OperatingMode;
+use tmag5170::limit_check::LimitCheck;
+use tmag5170::status::SysStatus;
+use tmag5170::system_config::{DataType, SystemConfig, TriggerMode};
+use tmag5170::Register;
+
+use common::setup_xyz;
+
+#[test]
+fn limit_bits_are_decoded() {
+    assert_eq!(
+        LimitCheck::from_sys_status(SysStatus::from_u16(0xfff0)),
+        LimitCheck::default()
+    );
+    assert!(!LimitCheck::default().any());
+
+    let bits = [
+        (
+            0b0001,
+            LimitCheck {
+                x: true,
+                ..LimitCheck::default()
+            },
+        ),
+        (
+            0b0010,
+            LimitCheck {
+                y: true,
+                ..LimitCheck::default()
+            },
+        ),
+        (
+            0b0100,
+            LimitCheck {
+                z: true,
+                ..LimitCheck::default()
+            },
+        ),
+        (
+            0b1000,
+            LimitCheck {
+                t: true,
+                ..LimitCheck::default()
+            },
+        ),
+    ];
+    for &(sys, limits) in bits.iter() {
+        let sys = SysStatus::from_u16(sys);
+        assert_eq!(sys.limit_check(), limits);
+        assert!(limits.any());
+    }
+}
+
+#[test]
+fn enabling_checks_keeps_the_configuration() {
+    let (sim, mut tmag5170) = setup_xyz(OperatingMode::Standby);
+    let system = SystemConfig::new()
+        .set_trigger_mode(TriggerMode::Cs)
+        .set_data_type(DataType::XY);
+    tmag5170.apply_system_config(system).unwrap();
+    tmag5170
+        .apply_system_config(system.set_data_type(DataType::Default))
+        .unwrap();
+
+    tmag5170
+        .enable_limit_checks(false, true, false, true)
+        .unwrap();
+    let config = tmag5170.config();
+    assert_eq!(config.system.trigger_mode(), Some(TriggerMode::Cs));
+    assert!(config.system.t_y_limit_check());
+    assert!(!config.system.t_x_limit_check());
+    assert!(config.device.t_limit_check_en());
+    assert!(config.device.t_en());
+    assert_eq!(
+        sim.register(Register::SYSTEM_CONFIG),
+        config.system.to_u16()
+    );
+    assert_eq!(
+        sim.register(Register::DEVICE_CONFIG),
+        config.device.to_u16()
+    );
+}
+
+#[test]
+fn violations_are_reported_with_the_measurement() {
+    let (sim, mut tmag5170) = setup_xyz(OperatingMode::Standby);
+    tmag5170
+        .enable_limit_checks(true, true, true, true)
+        .unwrap();
+
+    sim.set_field(0.0, -60.0, 0.0);
+    sim.set_temperature(190.0);
+    tmag5170.conv_start().unwrap();
+    let measurement = tmag5170.read_with_limits().unwrap();
+    assert!(measurement.y < 0);
+    assert_eq!(
+        measurement.limits,
+        LimitCheck {
+            y: true,
+            t: true,
+            ..LimitCheck::default()
+        }
+    );
+
+    sim.set_field(0.0, 0.0, 0.0);
+    sim.set_temperature(25.0);
+    tmag5170.conv_start().unwrap();
+    assert_eq!(tmag5170.read_limit_check().unwrap(), LimitCheck::default());
+}
+
+#[test]
+fn disabled_checks_are_not_reported() {
+    let (sim, mut tmag5170) = setup_xyz(OperatingMode::Standby);
+    tmag5170
+        .enable_limit_checks(false, false, true, false)
+        .unwrap();
+
+    sim.set_field(60.0, 60.0, 0.0);
+    sim.set_temperature(190.0);
+    tmag5170.conv_start().unwrap();
+    assert!(!tmag5170.read_with_limits().unwrap().limits.any());
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use core::convert::Infallible;
+
+use embedded_hal::blocking::spi::Transfer;
+
+use tmag5170::sim::SimDevice;
+use tmag5170::test_config::Version;
+use tmag5170::{ExtError, Register, Tmag5170};
+
+use common::setup;
+
+/// MISO line held at one level
+struct StuckMiso(u8);
+
+impl Transfer<u8> for StuckMiso {
+    type Error = Infallible;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Infallible> {
+        words.iter_mut().for_each(|w| *w = self.0);
+        Ok(words)
+    }
+}
+
+#[test]
+fn versions_are_identified() {
+    for &version in [Version::A1, Version::A2].iter() {
+        let (_sim, mut tmag5170) = setup(version);
+        assert_eq!(tmag5170.probe(), Ok(version));
+    }
+}
+
+#[test]
+fn floating_miso_is_not_present() {
+    let sim = SimDevice::new(Version::A1);
+    for &level in [0x00, 0xff].iter() {
+        let mut tmag5170 = Tmag5170::new(StuckMiso(level), sim.cs());
+        assert_eq!(tmag5170.probe(), Err(ExtError::NotPresent));
+        assert_eq!(tmag5170.read_mag_registers(), Err(ExtError::NotPresent));
+    }
+}
+
+#[test]
+fn device_is_detected_again_after_power_up() {
+    let (sim, mut tmag5170) = setup(Version::A2);
+
+    sim.set_powered(false);
+    assert_eq!(tmag5170.read_temp_register(), Err(ExtError::NotPresent));
+    sim.set_powered(true);
+    assert_eq!(tmag5170.probe(), Ok(Version::A2));
+}
+
+#[test]
+fn unknown_version_is_wrong_device() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+
+    sim.set_register(Register::TEST_CONFIG, 0b11 << 4);
+    assert_eq!(
+        tmag5170.probe(),
+        Err(ExtError::WrongDevice {
+            register: Register::TEST_CONFIG,
+            value: 0x0030,
+        })
+    );
+}
+
+#[test]
+fn reserved_bits_are_wrong_device() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+
+    sim.set_register(Register::TEST_CONFIG, 0x0100);
+    assert_eq!(
+        tmag5170.probe(),
+        Err(ExtError::WrongDevice {
+            register: Register::TEST_CONFIG,
+            value: 0x0100,
+        })
+    );
+
+    sim.set_register(Register::TEST_CONFIG, 0x0010);
+    sim.set_register(Register::ALERT_CONFIG, 0x8000);
+    assert_eq!(
+        tmag5170.probe(),
+        Err(ExtError::WrongDevice {
+            register: Register::ALERT_CONFIG,
+            value: 0x8000,
+        })
+    );
+}
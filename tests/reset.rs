@@ -0,0 +1,61 @@
+mod common;
+
+use tmag5170::device_config::OperatingMode;
+use tmag5170::reset::ResetSource;
+use tmag5170::sim::{SimCs, SimDevice, SimSpi};
+use tmag5170::system_config::{DataType, SystemConfig};
+use tmag5170::{Register, Tmag5170};
+
+use common::setup_xyz;
+
+/// Configured device whose power-up reset has been handled
+fn configured() -> (SimDevice, Tmag5170<SimSpi, SimCs>) {
+    let (sim, mut tmag5170) = setup_xyz(OperatingMode::Standby);
+    let event = tmag5170.check_reset().unwrap().unwrap();
+    assert_eq!(event.source, ResetSource::FrameStatus);
+    assert_eq!(tmag5170.check_reset(), Ok(None));
+    (sim, tmag5170)
+}
+
+#[test]
+fn afe_status_reset_is_detected() {
+    let (sim, mut tmag5170) = configured();
+    let device = tmag5170.config().device.to_u16();
+
+    sim.set_register(Register::DEVICE_CONFIG, 0x0000);
+    sim.set_register(Register::AFE_STATUS, 0x8000);
+    let event = tmag5170.check_reset().unwrap().unwrap();
+    assert_eq!(event.source, ResetSource::AfeStatus);
+    assert_eq!(tmag5170.take_reset_event(), Some(event));
+    assert_eq!(sim.register(Register::DEVICE_CONFIG), device);
+}
+
+#[test]
+fn shadow_mismatch_is_detected() {
+    let (sim, mut tmag5170) = configured();
+    let sensor = tmag5170.config().sensor.to_u16();
+
+    sim.set_register(Register::SENSOR_CONFIG, 0x0000);
+    let event = tmag5170.check_reset().unwrap().unwrap();
+    assert_eq!(event.source, ResetSource::ShadowMismatch);
+    assert_eq!(sim.register(Register::SENSOR_CONFIG), sensor);
+    assert_eq!(tmag5170.check_reset(), Ok(None));
+}
+
+#[test]
+fn special_frames_are_not_compared_with_the_shadow() {
+    let (sim, mut tmag5170) = configured();
+    let am = SystemConfig::new().set_data_type(DataType::AM);
+    tmag5170.apply_system_config(am).unwrap();
+    sim.set_field(10.0, 20.0, 0.0);
+    tmag5170.conv_start().unwrap();
+
+    let frames = sim.frames();
+    assert_eq!(tmag5170.check_reset(), Ok(None));
+    assert_eq!(sim.frames(), frames + 1);
+
+    sim.set_register(Register::AFE_STATUS, 0x8000);
+    let event = tmag5170.check_reset().unwrap().unwrap();
+    assert_eq!(event.source, ResetSource::FrameStatus);
+    assert_eq!(sim.register(Register::SYSTEM_CONFIG), am.to_u16());
+}
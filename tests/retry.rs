@@ -0,0 +1,128 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use embedded_hal::blocking::delay::DelayUs;
+
+use tmag5170::frame::FrameStatus;
+use tmag5170::retry::{DelayBackoff, RetryCounters, RetryPolicy};
+use tmag5170::sim::SimDevice;
+use tmag5170::test_config::Version;
+use tmag5170::{ExtError, Tmag5170};
+
+use common::{setup, BusFault, FlakySpi};
+
+/// Records every requested delay
+#[derive(Clone, Default)]
+struct RecordingDelay {
+    delays: Rc<RefCell<Vec<u32>>>,
+}
+
+impl DelayUs<u32> for RecordingDelay {
+    fn delay_us(&mut self, us: u32) {
+        self.delays.borrow_mut().push(us);
+    }
+}
+
+#[test]
+fn default_policy_does_not_retry() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+
+    sim.corrupt_responses(1);
+    assert_eq!(tmag5170.read_sys_status(), Err(ExtError::Crc));
+    assert_eq!(tmag5170.retry_counters(), RetryCounters::default());
+}
+
+#[test]
+fn exhausted_retries_are_counted() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    tmag5170.set_retry_policy(RetryPolicy::new().set_max_attempts(2));
+
+    sim.corrupt_responses(2);
+    assert_eq!(tmag5170.read_sys_status(), Err(ExtError::Crc));
+    let counters = tmag5170.retry_counters();
+    assert_eq!(counters.crc, 1);
+    assert_eq!(counters.exhausted, 1);
+
+    sim.corrupt_responses(1);
+    assert!(tmag5170.read_sys_status().is_ok());
+    let counters = tmag5170.retry_counters();
+    assert_eq!(counters.crc, 2);
+    assert_eq!(counters.exhausted, 1);
+    assert_eq!(counters.retries(), 2);
+
+    tmag5170.reset_retry_counters();
+    assert_eq!(tmag5170.retry_counters(), RetryCounters::default());
+}
+
+#[test]
+fn disabled_error_kinds_are_not_retried() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    let policy = RetryPolicy::new().set_max_attempts(3).set_retry_crc(false);
+    tmag5170.set_retry_policy(policy);
+
+    sim.corrupt_responses(1);
+    assert_eq!(tmag5170.read_sys_status(), Err(ExtError::Crc));
+    assert_eq!(tmag5170.retry_counters().retries(), 0);
+    assert_eq!(tmag5170.retry_counters().exhausted, 0);
+}
+
+#[test]
+fn spi_errors_are_retried_on_request() {
+    let sim = SimDevice::new(Version::A1);
+    let spi = FlakySpi::new(sim.spi(), 1);
+    let mut tmag5170 = Tmag5170::new(spi, sim.cs());
+    tmag5170.set_retry_policy(RetryPolicy::new().set_max_attempts(2));
+    assert_eq!(tmag5170.read_sys_status(), Err(ExtError::Spi(BusFault)));
+
+    let (mut spi, cs) = tmag5170.free();
+    spi.failures = 1;
+    let mut tmag5170 = Tmag5170::new(spi, cs);
+    let policy = RetryPolicy::new().set_max_attempts(2).set_retry_spi(true);
+    tmag5170.set_retry_policy(policy);
+    assert!(tmag5170.read_sys_status().is_ok());
+    assert_eq!(tmag5170.retry_counters().spi, 1);
+}
+
+#[test]
+fn backoff_grows_with_the_failed_attempts() {
+    let (sim, tmag5170) = setup(Version::A1);
+    let delay = RecordingDelay::default();
+    let mut tmag5170 = tmag5170.with_backoff(DelayBackoff::new(delay.clone()));
+    let policy = RetryPolicy::new().set_max_attempts(4).set_backoff_us(100);
+    tmag5170.set_retry_policy(policy);
+
+    sim.corrupt_responses(3);
+    assert!(tmag5170.read_sys_status().is_ok());
+    assert_eq!(*delay.delays.borrow(), vec![100, 200, 300]);
+}
+
+#[test]
+fn zero_backoff_does_not_wait() {
+    let (sim, tmag5170) = setup(Version::A1);
+    let delay = RecordingDelay::default();
+    let mut tmag5170 = tmag5170.with_backoff(DelayBackoff::new(delay.clone()));
+    tmag5170.set_retry_policy(RetryPolicy::new().set_max_attempts(2));
+
+    sim.corrupt_responses(1);
+    assert!(tmag5170.read_sys_status().is_ok());
+    assert!(delay.delays.borrow().is_empty());
+}
+
+#[test]
+fn policy_classifies_errors() {
+    type Error = ExtError<BusFault, ()>;
+    let policy = RetryPolicy::new().set_max_attempts(0);
+    assert_eq!(policy.max_attempts(), 1);
+
+    assert!(policy.is_retryable(&Error::Crc));
+    assert!(policy.is_retryable(&Error::Frame(FrameStatus::from_u16(0x800))));
+    assert!(!policy.is_retryable(&Error::Spi(BusFault)));
+    assert!(!policy.is_retryable(&Error::Timeout));
+    assert!(!policy.is_retryable(&Error::NotPresent));
+
+    let policy = policy.set_backoff_us(u32::MAX / 2);
+    assert_eq!(policy.backoff_us(1), u32::MAX / 2);
+    assert_eq!(policy.backoff_us(3), u32::MAX);
+}
@@ -0,0 +1,81 @@
+use tmag5170::angle_check::AngleTolerance;
+use tmag5170::device_config::{ConvAvg, DeviceConfig, OperatingMode};
+use tmag5170::scenario::{Converter, Noise, Ramp, RotatingMagnet, Sample, Scenario, Step};
+use tmag5170::sensor_config::{AngleEn, MagChEn, Range, SensorConfig};
+use tmag5170::sim::SimDevice;
+use tmag5170::test_config::Version;
+use tmag5170::Tmag5170;
+
+#[test]
+fn rotating_magnet_passes_angle_check() {
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+    let sensor = SensorConfig::new()
+        .set_mag_ch_en(MagChEn::Xy)
+        .set_angle_en(AngleEn::Xy);
+    tmag5170.apply_sensor_config(sensor).unwrap();
+    tmag5170
+        .apply_device_config(DeviceConfig::new().set_operating_mode(OperatingMode::Standby))
+        .unwrap();
+
+    let mut magnet = RotatingMagnet::new(30.0, 10.0).set_phase(15.0);
+    for i in 0..100 {
+        sim.set_sample(magnet.sample(i as f64 * 1e-3));
+        tmag5170.conv_start().unwrap();
+        let check = tmag5170.check_angle(AngleTolerance::new()).unwrap();
+        assert!(check.is_ok(), "{:?}", check);
+    }
+}
+
+#[test]
+fn codes_follow_range() {
+    let sensor = SensorConfig::new()
+        .set_x_range(Range::A1_25mT_A2_133mT)
+        .set_y_range(Range::A1_50mT_A2_200mT)
+        .set_z_range(Range::A1_100mT_A2_300mT);
+    let sample = Sample::new(12.5, 12.5, 12.5, 25.0);
+
+    let codes = Converter::new(Version::A1, sensor, DeviceConfig::new()).codes(sample);
+    assert_eq!(
+        (codes.x, codes.y, codes.z, codes.t),
+        (16384, 8192, 4096, 17522)
+    );
+
+    let codes = Converter::new(Version::A2, sensor, DeviceConfig::new()).codes(sample);
+    assert_eq!((codes.x, codes.y, codes.z), (3080, 2048, 1365));
+}
+
+#[test]
+fn averaging_reduces_noise() {
+    let sensor = SensorConfig::new().set_mag_ch_en(MagChEn::X);
+    let quiet = Sample::new(0.0, 0.0, 0.0, 25.0);
+
+    let spread = |conv_avg: ConvAvg| {
+        let device = DeviceConfig::new().set_conv_avg(conv_avg);
+        let converter = Converter::new(Version::A1, sensor, device);
+        let mut noise = Noise::new(Step::new(quiet, quiet, 0.0), 0.5, 1);
+        let sum: f64 = (0..200)
+            .map(|i| {
+                let x = converter.convert(&mut noise, i as f64 * 1e-3).x as f64;
+                x * x
+            })
+            .sum();
+        (sum / 200.0).sqrt()
+    };
+
+    assert!(spread(ConvAvg::Avg32x) < spread(ConvAvg::Avg1x) / 3.0);
+}
+
+#[test]
+fn ramp_interpolates() {
+    let mut ramp = Ramp::new(
+        Sample::new(0.0, 0.0, 0.0, 20.0),
+        Sample::new(10.0, -10.0, 4.0, 30.0),
+        1.0,
+        2.0,
+    );
+
+    assert_eq!(ramp.sample(0.0), Sample::new(0.0, 0.0, 0.0, 20.0));
+    assert_eq!(ramp.sample(1.5), Sample::new(5.0, -5.0, 2.0, 25.0));
+    assert_eq!(ramp.sample(3.0), Sample::new(10.0, -10.0, 4.0, 30.0));
+}
@@ -0,0 +1,95 @@
+use serde_json::json;
+use tmag5170::alert_config::{AlertConfig, ThrxCount};
+use tmag5170::device_config::{ConvAvg, DeviceConfig, OperatingMode};
+use tmag5170::frame::FrameStatus;
+use tmag5170::limit_check::{LimitCheck, LimitedMeasurement};
+use tmag5170::sensor_config::{MagChEn, Range, SensorConfig};
+use tmag5170::status::{AfeStatus, ConvStatus, SysStatus};
+use tmag5170::system_config::{DataType, SystemConfig};
+use tmag5170::test_config::TestConfig;
+use tmag5170::validation::ConfigSet;
+
+#[test]
+fn configs_serialize_by_field() {
+    let device = DeviceConfig::new()
+        .set_conv_avg(ConvAvg::Avg8x)
+        .set_operating_mode(OperatingMode::Standby)
+        .set_t_en(true);
+    let value = serde_json::to_value(device).unwrap();
+
+    assert_eq!(value["conv_avg"], json!("Avg8x"));
+    assert_eq!(value["operating_mode"], json!("Standby"));
+    assert_eq!(value["t_en"], json!(true));
+    assert_eq!(value["t_comp_en"], json!(false));
+}
+
+#[test]
+fn configs_round_trip() {
+    let set = ConfigSet {
+        device: DeviceConfig::new().set_conv_avg(ConvAvg::Avg32x),
+        sensor: SensorConfig::new()
+            .set_mag_ch_en(MagChEn::Xyz)
+            .set_x_range(Range::A1_100mT_A2_300mT),
+        system: SystemConfig::new().set_data_type(DataType::AM),
+        alert: AlertConfig::new().set_thrx_count(ThrxCount::ConvResult3),
+    };
+    let text = serde_json::to_string(&set).unwrap();
+    assert_eq!(serde_json::from_str::<ConfigSet>(&text).unwrap(), set);
+
+    let test = TestConfig::form_u16(0x0014);
+    let text = serde_json::to_string(&test).unwrap();
+    assert!(text.contains("\"ver\":\"A2\""), "{}", text);
+    assert_eq!(serde_json::from_str::<TestConfig>(&text).unwrap(), test);
+}
+
+#[test]
+fn reserved_codes_fail_to_serialize() {
+    let device = DeviceConfig::form_u16(0x7000);
+    assert!(serde_json::to_string(&device).is_err());
+
+    let sensor = SensorConfig::form_u16(0x0003);
+    assert!(serde_json::to_string(&sensor).is_err());
+}
+
+#[test]
+fn status_and_measurements_round_trip() {
+    let conv = ConvStatus::from_u16(0x2751);
+    let text = serde_json::to_string(&conv).unwrap();
+    assert!(text.contains("\"set_count\":5"), "{}", text);
+    assert_eq!(serde_json::from_str::<ConvStatus>(&text).unwrap(), conv);
+
+    let afe = AfeStatus::from_u16(0x9101);
+    let text = serde_json::to_string(&afe).unwrap();
+    assert_eq!(serde_json::from_str::<AfeStatus>(&text).unwrap(), afe);
+
+    let sys = SysStatus::from_u16(0xd239);
+    let text = serde_json::to_string(&sys).unwrap();
+    assert_eq!(serde_json::from_str::<SysStatus>(&text).unwrap(), sys);
+
+    let frame = FrameStatus::from_u16(0x0ff0);
+    let text = serde_json::to_string(&frame).unwrap();
+    assert_eq!(serde_json::from_str::<FrameStatus>(&text).unwrap(), frame);
+
+    let measurement = LimitedMeasurement {
+        x: -12,
+        y: 300,
+        z: 0,
+        t: 5000,
+        limits: LimitCheck {
+            x: true,
+            ..LimitCheck::default()
+        },
+    };
+    let text = serde_json::to_string(&measurement).unwrap();
+    assert_eq!(
+        serde_json::from_str::<LimitedMeasurement>(&text).unwrap(),
+        measurement
+    );
+
+    let bad = text.replace("5000", "70000");
+    assert!(serde_json::from_str::<LimitedMeasurement>(&bad).is_err());
+    assert!(serde_json::from_str::<ConvStatus>(
+        r#"{"rdy":true,"a":false,"t":false,"z":false,"y":false,"x":false,"set_count":8,"alrt_status":0}"#
+    )
+    .is_err());
+}
@@ -0,0 +1,537 @@
+mod common;
+
+use crc_all::Crc;
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::blocking::spi::Transfer;
+
+use tmag5170::angle_check::AngleTolerance;
+use tmag5170::device_config::{DeviceConfig, OperatingMode};
+use tmag5170::diagnostics::Mechanism;
+use tmag5170::dump::RegisterDump;
+use tmag5170::reset::ResetSource;
+use tmag5170::retry::RetryPolicy;
+use tmag5170::sensor_config::{AngleEn, MagChEn, SensorConfig};
+use tmag5170::sim::{SimCs, SimDevice, SimSpi};
+use tmag5170::system_config::{DataType, DiagSel, SystemConfig, TriggerMode};
+use tmag5170::test_config::Version;
+use tmag5170::{ExtError, Register, Tmag5170};
+
+use common::setup;
+
+/// SPI bus which flips `bits` in every read of `reg` and fixes up the CRC
+struct FlippedReadback {
+    inner: SimSpi,
+    crc: Crc<u8>,
+    reg: Register,
+    bits: u16,
+}
+
+impl FlippedReadback {
+    fn new(inner: SimSpi, reg: Register, bits: u16) -> Self {
+        let crc = Crc::<u8>::new(0x03, 4, 0x0f, 0x00, false);
+        FlippedReadback {
+            inner,
+            crc,
+            reg,
+            bits,
+        }
+    }
+}
+
+impl Transfer<u8> for FlippedReadback {
+    type Error = core::convert::Infallible;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Self::Error> {
+        let flip = words[0] == 0x80 | self.reg as u8;
+        self.inner.transfer(words)?;
+        if flip {
+            let bits = self.bits.to_be_bytes();
+            words[1] ^= bits[0];
+            words[2] ^= bits[1];
+            words[3] &= 0xf0;
+            self.crc.init();
+            self.crc.update(words);
+            words[3] |= self.crc.finish();
+        }
+        Ok(words)
+    }
+}
+
+struct NoDelay;
+
+impl DelayUs<u32> for NoDelay {
+    fn delay_us(&mut self, _us: u32) {}
+}
+
+/// Delay during which the device finishes a conversion after `convert_at` calls
+struct ConvertingDelay {
+    sim: SimDevice,
+    calls: u32,
+    convert_at: u32,
+}
+
+impl DelayUs<u32> for ConvertingDelay {
+    fn delay_us(&mut self, _us: u32) {
+        self.calls += 1;
+        if self.calls == self.convert_at {
+            self.sim.convert();
+        }
+    }
+}
+
+fn standby_xyz(tmag5170: &mut Tmag5170<SimSpi, SimCs>) {
+    let sensor = SensorConfig::new()
+        .set_mag_ch_en(MagChEn::Xyz)
+        .set_angle_en(AngleEn::Xy);
+    tmag5170.apply_sensor_config(sensor).unwrap();
+    let device = DeviceConfig::new()
+        .set_operating_mode(OperatingMode::Standby)
+        .set_t_en(true);
+    tmag5170.apply_device_config(device).unwrap();
+}
+
+#[test]
+fn new_device_reports_cfg_reset_once() {
+    let (_sim, mut tmag5170) = setup(Version::A1);
+
+    assert!(tmag5170.read_afe_status().unwrap().cfg_reset());
+    assert!(!tmag5170.read_afe_status().unwrap().cfg_reset());
+}
+
+#[test]
+fn configs_are_written() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    standby_xyz(&mut tmag5170);
+
+    let config = tmag5170.config();
+    assert_eq!(
+        sim.register(Register::SENSOR_CONFIG),
+        config.sensor.to_u16()
+    );
+    assert_eq!(
+        sim.register(Register::DEVICE_CONFIG),
+        config.device.to_u16()
+    );
+    assert_eq!(
+        tmag5170.read_sys_status().unwrap().operating_stat(),
+        OperatingMode::Standby as u8
+    );
+}
+
+#[test]
+fn reserved_bits_are_ignored_by_the_device() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    tmag5170.set_write_verify(true);
+
+    tmag5170
+        .apply_device_config(DeviceConfig::form_u16(DeviceConfig::RESERVED_BITS))
+        .unwrap();
+    assert_eq!(sim.register(Register::DEVICE_CONFIG), 0x0000);
+}
+
+#[test]
+fn write_verify_reports_mismatch() {
+    let sim = SimDevice::new(Version::A1);
+    let spi = FlippedReadback::new(sim.spi(), Register::DEVICE_CONFIG, 0x0010);
+    let mut tmag5170 = Tmag5170::new(spi, sim.cs());
+    tmag5170.set_write_verify(true);
+
+    let device = DeviceConfig::new().set_operating_mode(OperatingMode::Standby);
+    assert_eq!(
+        tmag5170.apply_device_config(device),
+        Err(ExtError::Mismatch {
+            register: Register::DEVICE_CONFIG,
+            expected: 0x0010,
+            actual: 0x0000,
+            bits: 0x0010,
+        })
+    );
+    assert_eq!(sim.register(Register::DEVICE_CONFIG), 0x0010);
+}
+
+#[test]
+fn write_verify_ignores_reserved_and_read_only_bits() {
+    let sim = SimDevice::new(Version::A1);
+    let spi = FlippedReadback::new(sim.spi(), Register::DEVICE_CONFIG, 0x0080);
+    let mut tmag5170 = Tmag5170::new(spi, sim.cs());
+    tmag5170.set_write_verify(true);
+    let device = DeviceConfig::new().set_operating_mode(OperatingMode::Standby);
+    assert_eq!(tmag5170.apply_device_config(device), Ok(()));
+
+    // VER is read only
+    let spi = FlippedReadback::new(sim.spi(), Register::TEST_CONFIG, 0x0030);
+    let mut tmag5170 = Tmag5170::new(spi, sim.cs());
+    tmag5170.set_write_verify(true);
+    let dump = RegisterDump::new().set(Register::TEST_CONFIG, 0x0001);
+    assert_eq!(tmag5170.restore_test_config(&dump), Ok(()));
+    assert_eq!(sim.register(Register::TEST_CONFIG), 0x0001);
+}
+
+#[test]
+fn write_verify_skips_special_frames() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    standby_xyz(&mut tmag5170);
+    tmag5170.set_write_verify(true);
+
+    let am = SystemConfig::new().set_data_type(DataType::AM);
+    tmag5170.apply_system_config(am).unwrap();
+    assert_eq!(sim.register(Register::SYSTEM_CONFIG), am.to_u16());
+
+    tmag5170.apply_system_config(SystemConfig::new()).unwrap();
+    assert_eq!(sim.register(Register::SYSTEM_CONFIG), 0x0000);
+}
+
+#[test]
+fn write_verify_skips_deep_sleep() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    tmag5170.set_write_verify(true);
+
+    let frames = sim.frames();
+    let device = DeviceConfig::new().set_operating_mode(OperatingMode::DeepSleep);
+    tmag5170.apply_device_config(device).unwrap();
+    assert_eq!(sim.frames(), frames + 1);
+    assert_eq!(sim.register(Register::DEVICE_CONFIG), device.to_u16());
+
+    // The next frame only wakes the device up
+    assert_eq!(tmag5170.read_sys_status(), Err(ExtError::NotPresent));
+    assert_eq!(sim.register(Register::DEVICE_CONFIG), 0x0000);
+    assert!(tmag5170.read_sys_status().is_ok());
+}
+
+#[test]
+fn results_follow_field_after_conversion() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    standby_xyz(&mut tmag5170);
+
+    sim.set_field(25.0, -12.5, 50.0);
+    assert_eq!(tmag5170.read_mag_registers().unwrap(), (0, 0, 0));
+
+    tmag5170.conv_start().unwrap();
+    assert_eq!(
+        tmag5170.read_mag_registers().unwrap(),
+        (16384, -8192, 32767)
+    );
+    assert_eq!(tmag5170.read_temp_register().unwrap(), 17522);
+
+    let conv = tmag5170.read_conv_status().unwrap();
+    assert!(conv.rdy());
+    assert_eq!(conv.set_count(), 1);
+}
+
+#[test]
+fn active_mode_converts_continuously() {
+    let (sim, mut tmag5170) = setup(Version::A2);
+    tmag5170
+        .apply_sensor_config(SensorConfig::new().set_mag_ch_en(MagChEn::X))
+        .unwrap();
+    tmag5170
+        .apply_device_config(DeviceConfig::new().set_operating_mode(OperatingMode::Active))
+        .unwrap();
+
+    sim.set_field(100.0, 0.0, 0.0);
+    let (x, _, _) = tmag5170.read_mag_registers().unwrap();
+    assert_eq!(x, 16384);
+}
+
+#[test]
+fn angle_matches_software_calculation() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    standby_xyz(&mut tmag5170);
+
+    sim.set_field(10.0, 10.0, 0.0);
+    tmag5170.conv_start().unwrap();
+    let (angle, _) = tmag5170.read_angle_registers().unwrap();
+    assert_eq!(angle, 45 * 16);
+
+    let check = tmag5170.check_angle(AngleTolerance::new()).unwrap();
+    assert!(check.is_ok(), "{:?}", check);
+}
+
+#[test]
+fn angle_check_needs_angle_calculation() {
+    let (_sim, mut tmag5170) = setup(Version::A1);
+    tmag5170
+        .apply_sensor_config(SensorConfig::new().set_mag_ch_en(MagChEn::Xyz))
+        .unwrap();
+
+    assert_eq!(
+        tmag5170.check_angle(AngleTolerance::new()),
+        Err(ExtError::AngleDisabled)
+    );
+}
+
+#[test]
+fn am_special_read() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    standby_xyz(&mut tmag5170);
+
+    sim.set_field(0.0, 25.0, 0.0);
+    tmag5170.conv_start().unwrap();
+    tmag5170
+        .apply_system_config(SystemConfig::new().set_data_type(DataType::AM))
+        .unwrap();
+
+    assert_eq!(tmag5170.read_am().unwrap(), (90 * 8, 1024));
+}
+
+#[test]
+fn corrupted_response_is_crc_error() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+
+    sim.corrupt_responses(1);
+    assert_eq!(tmag5170.read_sys_status(), Err(ExtError::Crc));
+    assert!(tmag5170.read_sys_status().is_ok());
+}
+
+#[test]
+fn retry_recovers_from_crc_error() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    tmag5170.set_retry_policy(RetryPolicy::new().set_max_attempts(3));
+
+    sim.corrupt_responses(2);
+    assert!(tmag5170.read_sys_status().is_ok());
+    assert_eq!(tmag5170.retry_counters().crc, 2);
+}
+
+#[test]
+fn writes_are_not_confirmed_without_retry() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    let device = DeviceConfig::new().set_operating_mode(OperatingMode::Standby);
+    tmag5170.apply_device_config(device).unwrap();
+    tmag5170.conv_start().unwrap();
+    assert_eq!(sim.frames(), 2);
+
+    sim.corrupt_requests(1);
+    tmag5170.apply_device_config(DeviceConfig::new()).unwrap();
+    assert_eq!(sim.register(Register::DEVICE_CONFIG), device.to_u16());
+    assert!(tmag5170.read_sys_status().is_ok());
+    assert!(tmag5170.frame_status().prev_crc_error());
+}
+
+#[test]
+fn corrupted_write_is_reported() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    tmag5170.set_retry_policy(RetryPolicy::new().set_confirm(true));
+    tmag5170.read_afe_status().unwrap();
+
+    sim.corrupt_requests(1);
+    let device = DeviceConfig::new().set_operating_mode(OperatingMode::Standby);
+    match tmag5170.apply_device_config(device) {
+        Err(ExtError::Frame(status)) => assert!(status.prev_crc_error()),
+        res => panic!("unexpected {:?}", res),
+    }
+    assert_eq!(sim.register(Register::DEVICE_CONFIG), 0x0000);
+    assert_eq!(tmag5170.config().device, DeviceConfig::new());
+
+    // The ignored write doesn't leak into the next transaction
+    assert!(tmag5170.read_sys_status().is_ok());
+}
+
+#[test]
+fn corrupted_write_is_retried() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    tmag5170.set_retry_policy(RetryPolicy::new().set_max_attempts(2));
+
+    sim.corrupt_requests(1);
+    let device = DeviceConfig::new().set_operating_mode(OperatingMode::Standby);
+    tmag5170.apply_device_config(device).unwrap();
+    assert_eq!(sim.register(Register::DEVICE_CONFIG), device.to_u16());
+    assert_eq!(tmag5170.retry_counters().frame, 1);
+}
+
+#[test]
+fn corrupted_conversion_start_is_reported() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    standby_xyz(&mut tmag5170);
+    tmag5170.set_retry_policy(RetryPolicy::new().set_max_attempts(3));
+
+    sim.corrupt_requests(1);
+    assert!(matches!(tmag5170.conv_start(), Err(ExtError::Frame(_))));
+    assert_eq!(tmag5170.retry_counters().retries(), 0);
+    assert!(!tmag5170.read_conv_status().unwrap().rdy());
+}
+
+#[test]
+fn conversion_start_is_not_repeated() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    standby_xyz(&mut tmag5170);
+    tmag5170.set_retry_policy(RetryPolicy::new().set_max_attempts(3));
+
+    sim.corrupt_responses(1);
+    assert_eq!(tmag5170.conv_start(), Err(ExtError::Crc));
+    assert_eq!(tmag5170.retry_counters().retries(), 0);
+    assert_eq!(tmag5170.read_conv_status().unwrap().set_count(), 1);
+}
+
+#[test]
+fn corrupted_data_type_switch_is_reported_or_written() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    standby_xyz(&mut tmag5170);
+    sim.set_field(0.0, -25.0, 0.0);
+    tmag5170.conv_start().unwrap();
+    tmag5170.set_retry_policy(RetryPolicy::new().set_confirm(true));
+
+    let xy = SystemConfig::new().set_data_type(DataType::XY);
+    sim.corrupt_requests(1);
+    assert!(matches!(
+        tmag5170.apply_system_config(xy),
+        Err(ExtError::Frame(_))
+    ));
+    assert_eq!(sim.register(Register::SYSTEM_CONFIG), 0x0000);
+    assert!(tmag5170.read_sys_status().is_ok());
+
+    tmag5170.apply_system_config(xy).unwrap();
+    assert_eq!(sim.register(Register::SYSTEM_CONFIG), xy.to_u16());
+
+    let default = SystemConfig::new();
+    sim.corrupt_requests(1);
+    assert!(matches!(
+        tmag5170.apply_system_config(default),
+        Err(ExtError::Frame(_))
+    ));
+    assert_eq!(sim.register(Register::SYSTEM_CONFIG), xy.to_u16());
+
+    tmag5170.apply_system_config(default).unwrap();
+    assert_eq!(sim.register(Register::SYSTEM_CONFIG), 0x0000);
+    assert!(tmag5170.read_sys_status().is_ok());
+}
+
+#[test]
+fn unpowered_device_is_not_present() {
+    let (sim, mut tmag5170) = setup(Version::A2);
+    assert_eq!(tmag5170.probe(), Ok(Version::A2));
+
+    sim.set_powered(false);
+    assert_eq!(tmag5170.probe(), Err(ExtError::NotPresent));
+}
+
+#[test]
+fn power_cycle_is_reconfigured() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    tmag5170.read_afe_status().unwrap();
+    standby_xyz(&mut tmag5170);
+    tmag5170.set_auto_reconfigure(true);
+
+    sim.set_powered(false);
+    sim.set_powered(true);
+    assert_eq!(sim.register(Register::DEVICE_CONFIG), 0x0000);
+
+    tmag5170.read_sys_status().unwrap();
+    let event = tmag5170.take_reset_event().unwrap();
+    assert_eq!(event.source, ResetSource::FrameStatus);
+    assert_eq!(
+        sim.register(Register::DEVICE_CONFIG),
+        tmag5170.config().device.to_u16()
+    );
+}
+
+#[test]
+fn limit_check_is_reported() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    standby_xyz(&mut tmag5170);
+    tmag5170
+        .enable_limit_checks(true, true, true, false)
+        .unwrap();
+
+    sim.set_field(60.0, 0.0, 0.0);
+    tmag5170.conv_start().unwrap();
+    let measurement = tmag5170.read_with_limits().unwrap();
+    assert!(measurement.limits.x);
+    assert!(!measurement.limits.y);
+    assert!(!measurement.limits.z);
+}
+
+#[test]
+fn self_test_passes() {
+    let (_sim, mut tmag5170) = setup(Version::A1);
+    standby_xyz(&mut tmag5170);
+
+    let report = tmag5170.self_test(&mut NoDelay, 1000).unwrap();
+    assert_eq!(report.version(), Some(Version::A1));
+    assert!(report.is_ok(), "{:?}", report);
+}
+
+#[test]
+fn diagnostics_wait_for_a_new_conversion() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    standby_xyz(&mut tmag5170);
+    tmag5170.conv_start().unwrap();
+    assert!(tmag5170.read_conv_status().unwrap().rdy());
+
+    // Conversions are only started by the ALERT pin, RDY is stale
+    let alert_trigger = SystemConfig::new().set_trigger_mode(TriggerMode::Alert);
+    tmag5170.apply_system_config(alert_trigger).unwrap();
+    let mut delay = ConvertingDelay {
+        sim: sim.clone(),
+        calls: 0,
+        convert_at: 3,
+    };
+    let report = tmag5170
+        .run_diagnostics(DiagSel::AllData, &mut delay, 1000)
+        .unwrap();
+    assert!(report.is_ok(), "{:?}", report);
+    assert_eq!(delay.calls, 3);
+
+    assert_eq!(
+        tmag5170.run_diagnostics(DiagSel::AllData, &mut NoDelay, 1000),
+        Err(ExtError::Timeout)
+    );
+}
+
+/// Delay during which a diagnostic run finds faults and completes
+struct FaultingDelay {
+    sim: SimDevice,
+    afe: u16,
+    sys: u16,
+}
+
+impl DelayUs<u32> for FaultingDelay {
+    fn delay_us(&mut self, _us: u32) {
+        self.sim.set_register(Register::AFE_STATUS, self.afe);
+        self.sim.set_register(Register::SYS_STATUS, self.sys);
+        self.sim.convert();
+    }
+}
+
+#[test]
+fn diagnostics_report_failed_mechanisms() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    standby_xyz(&mut tmag5170);
+    let alert_trigger = SystemConfig::new().set_trigger_mode(TriggerMode::Alert);
+    tmag5170.apply_system_config(alert_trigger).unwrap();
+
+    // XHS_STAT and SDO_DRV come up during the run
+    let mut delay = FaultingDelay {
+        sim: sim.clone(),
+        afe: 0b1 << 8,
+        sys: 0b1 << 13,
+    };
+    let report = tmag5170
+        .run_diagnostics(DiagSel::AllData, &mut delay, 1000)
+        .unwrap();
+    assert!(!report.is_ok());
+    assert!(report.is_failed(Mechanism::XHall));
+    assert!(report.is_failed(Mechanism::SdoDrive));
+    let failed: Vec<_> = report.failed().collect();
+    assert_eq!(failed, vec![Mechanism::XHall, Mechanism::SdoDrive]);
+    assert_eq!(report.passed().count(), Mechanism::ALL.len() - 2);
+}
+
+#[test]
+fn diagnostics_restore_system_config_of_the_device() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    standby_xyz(&mut tmag5170);
+
+    let system = SystemConfig::new().set_trigger_mode(TriggerMode::Cs);
+    sim.set_register(Register::SYSTEM_CONFIG, system.to_u16());
+    let mut delay = ConvertingDelay {
+        sim: sim.clone(),
+        calls: 0,
+        convert_at: 1,
+    };
+    tmag5170
+        .run_diagnostics(DiagSel::AllData, &mut delay, 1000)
+        .unwrap();
+    assert_eq!(sim.register(Register::SYSTEM_CONFIG), system.to_u16());
+    assert_eq!(tmag5170.config().system, system);
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use tmag5170::device_config::{DeviceConfig, OperatingMode};
+use tmag5170::retry::RetryPolicy;
+use tmag5170::stats::LinkStats;
+use tmag5170::test_config::Version;
+use tmag5170::{ExtError, Register, Tmag5170};
+
+use common::{setup, FailingPin, PinFault};
+
+const CRC_STAT: u16 = 0b1 << 12;
+const FRAME_STAT: u16 = 0b1 << 11;
+
+#[test]
+fn every_frame_is_counted() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+
+    tmag5170.read_sys_status().unwrap();
+    tmag5170
+        .apply_device_config(DeviceConfig::new().set_operating_mode(OperatingMode::Standby))
+        .unwrap();
+    let stats = tmag5170.link_stats();
+    assert_eq!(stats.frames, sim.frames());
+    assert_eq!(stats.failed_frames(), 0);
+
+    tmag5170.reset_link_stats();
+    assert_eq!(tmag5170.link_stats(), LinkStats::default());
+}
+
+#[test]
+fn host_side_failures_are_counted() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+    tmag5170.set_retry_policy(RetryPolicy::new().set_max_attempts(3));
+
+    sim.corrupt_responses(2);
+    tmag5170.read_sys_status().unwrap();
+    let stats = tmag5170.link_stats();
+    assert_eq!(stats.frames, 3);
+    assert_eq!(stats.crc_errors, 2);
+    assert_eq!(stats.retries, 2);
+    assert_eq!(stats.failed_frames(), 2);
+
+    let mut tmag5170 = Tmag5170::new(sim.spi(), FailingPin::new(1));
+    assert_eq!(tmag5170.read_sys_status(), Err(ExtError::Pin(PinFault)));
+    let stats = tmag5170.link_stats();
+    assert_eq!(stats.pin_errors, 1);
+    assert_eq!(stats.failed_frames(), 1);
+}
+
+#[test]
+fn device_side_failures_are_counted() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+
+    sim.corrupt_requests(1);
+    let device = DeviceConfig::new().set_operating_mode(OperatingMode::Standby);
+    tmag5170.apply_device_config(device).unwrap();
+    assert_eq!(tmag5170.link_stats().rejected_frames, 0);
+
+    tmag5170.read_sys_status().unwrap();
+    let stats = tmag5170.link_stats();
+    assert_eq!(stats.rejected_frames, 1);
+    assert_eq!(stats.device_crc_errors, 1);
+    assert_eq!(stats.device_frame_errors, 0);
+
+    sim.set_register(Register::SYS_STATUS, CRC_STAT | FRAME_STAT);
+    tmag5170.read_sys_status().unwrap();
+    let stats = tmag5170.link_stats();
+    assert_eq!(stats.device_crc_errors, 2);
+    assert_eq!(stats.device_frame_errors, 1);
+    assert_eq!(stats.failed_frames(), 0);
+}
+
+#[test]
+fn ignored_reads_are_counted() {
+    let (sim, mut tmag5170) = setup(Version::A1);
+
+    sim.corrupt_requests(1);
+    tmag5170.read_sys_status().unwrap();
+    assert_eq!(tmag5170.link_stats().rejected_frames, 0);
+    tmag5170.read_sys_status().unwrap();
+    assert_eq!(tmag5170.link_stats().rejected_frames, 1);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use tmag5170::device_config::{ConvAvg, OperatingMode};
+use tmag5170::health::Fault;
+use tmag5170::stuck::{StuckDetector, StuckOutputs};
+
+use common::setup_xyz;
+
+#[test]
+fn standby_results_are_not_stuck() {
+    let (sim, mut tmag5170) = setup_xyz(OperatingMode::Standby);
+    let mut detector = StuckDetector::new(2);
+
+    sim.set_field(5.0, 5.0, 5.0);
+    tmag5170.conv_start().unwrap();
+    for _ in 0..10 {
+        tmag5170
+            .read_mag_registers_monitored(&mut detector)
+            .unwrap();
+    }
+    assert_eq!(detector.stuck(), StuckOutputs::default());
+}
+
+#[test]
+fn repeated_triggered_results_are_stuck() {
+    let (sim, mut tmag5170) = setup_xyz(OperatingMode::Standby);
+    let mut detector = StuckDetector::new(2);
+
+    sim.set_field(5.0, 5.0, 5.0);
+    for _ in 0..3 {
+        tmag5170.conv_start().unwrap();
+        tmag5170
+            .read_mag_registers_monitored(&mut detector)
+            .unwrap();
+    }
+    let stuck = detector.stuck();
+    assert!(stuck.x && stuck.y && stuck.z, "{:?}", stuck);
+    assert!(!stuck.set_count);
+}
+
+#[test]
+fn active_results_follow_the_field() {
+    let (sim, mut tmag5170) = setup_xyz(OperatingMode::Active);
+    let mut detector = StuckDetector::new(2);
+
+    for i in 0..10 {
+        sim.set_field(i as f32, 0.5 * i as f32, -(i as f32));
+        tmag5170
+            .read_mag_registers_monitored(&mut detector)
+            .unwrap();
+        assert!(!detector.stuck().any(), "{}: {:?}", i, detector.stuck());
+    }
+
+    for _ in 0..3 {
+        tmag5170
+            .read_mag_registers_monitored(&mut detector)
+            .unwrap();
+    }
+    let stuck = detector.stuck();
+    assert!(stuck.x && !stuck.set_count, "{:?}", stuck);
+}
+
+#[test]
+fn allowance_grows_with_averaging() {
+    let detector = StuckDetector::new(4);
+    assert_eq!(detector.limit(Some(ConvAvg::Avg1x)), 4);
+    assert_eq!(detector.limit(Some(ConvAvg::Avg2x)), 4);
+    assert_eq!(detector.limit(Some(ConvAvg::Avg4x)), 8);
+    assert_eq!(detector.limit(Some(ConvAvg::Avg8x)), 12);
+    assert_eq!(detector.limit(Some(ConvAvg::Avg16x)), 16);
+    assert_eq!(detector.limit(Some(ConvAvg::Avg32x)), 24);
+    assert_eq!(detector.limit(None), 24);
+    assert_eq!(StuckDetector::new(u16::MAX).limit(None), u16::MAX);
+    assert_eq!(StuckDetector::default().limit(Some(ConvAvg::Avg1x)), 8);
+}
+
+#[test]
+fn repeated_codes_are_flagged_per_channel() {
+    let mut detector = StuckDetector::new(2);
+    let avg = Some(ConvAvg::Avg1x);
+
+    detector.update_mag(avg, 1, 2, 3);
+    detector.update_mag(avg, 1, 5, 3);
+    let stuck = detector.update_mag(avg, 1, 6, 4);
+    assert_eq!(
+        stuck,
+        StuckOutputs {
+            x: true,
+            ..StuckOutputs::default()
+        }
+    );
+    assert_eq!(stuck.fault(), Some(Fault::StuckOutput));
+
+    let stuck = detector.update_mag(avg, 2, 6, 4);
+    assert!(!stuck.any());
+    assert_eq!(stuck.fault(), None);
+}
+
+#[test]
+fn angle_and_set_count_are_flagged() {
+    let mut detector = StuckDetector::new(1);
+    let avg = Some(ConvAvg::Avg1x);
+
+    detector.update_am(avg, 720, 100);
+    let stuck = detector.update_am(avg, 720, 101);
+    assert!(stuck.angle && !stuck.magnitude, "{:?}", stuck);
+
+    detector.update_set_count(3);
+    let stuck = detector.update_set_count(3);
+    assert!(stuck.set_count && stuck.angle, "{:?}", stuck);
+    assert_eq!(detector.stuck(), stuck);
+
+    detector.reset();
+    assert_eq!(detector.stuck(), StuckOutputs::default());
+    assert!(!detector.update_set_count(3).any());
+}
@@ -0,0 +1,183 @@
+use core::convert::Infallible;
+
+use embedded_hal::digital::v2::InputPin;
+
+use tmag5170::alert_config::AlertConfig;
+use tmag5170::sim::SimDevice;
+use tmag5170::status::SysStatus;
+use tmag5170::supply::{SupplyEvent, SupplyFault, SupplyMonitor};
+use tmag5170::test_config::Version;
+use tmag5170::{Register, Tmag5170};
+
+const VCC_OV: u16 = 0b1 << 5;
+const VCC_UV: u16 = 0b1 << 4;
+
+struct AlertPin {
+    low: bool,
+}
+
+impl InputPin for AlertPin {
+    type Error = Infallible;
+
+    fn is_high(&self) -> Result<bool, Infallible> {
+        Ok(!self.low)
+    }
+
+    fn is_low(&self) -> Result<bool, Infallible> {
+        Ok(self.low)
+    }
+}
+
+fn events(monitor: &mut SupplyMonitor, sys: u16) -> Vec<SupplyEvent> {
+    monitor.update(SysStatus::from_u16(sys)).collect()
+}
+
+fn event(fault: SupplyFault, active: bool) -> SupplyEvent {
+    SupplyEvent { fault, active }
+}
+
+#[test]
+fn fault_is_reported_after_the_debounce_samples() {
+    let mut monitor = SupplyMonitor::new(3);
+
+    assert!(events(&mut monitor, VCC_UV).is_empty());
+    assert!(events(&mut monitor, VCC_UV).is_empty());
+    assert_eq!(
+        events(&mut monitor, VCC_UV),
+        vec![event(SupplyFault::Undervoltage, true)]
+    );
+    assert!(monitor.is_undervoltage());
+    assert!(events(&mut monitor, VCC_UV).is_empty());
+
+    assert!(events(&mut monitor, 0).is_empty());
+    assert!(events(&mut monitor, 0).is_empty());
+    assert_eq!(
+        events(&mut monitor, 0),
+        vec![event(SupplyFault::Undervoltage, false)]
+    );
+    assert!(!monitor.is_undervoltage());
+}
+
+#[test]
+fn glitches_are_filtered() {
+    let mut monitor = SupplyMonitor::new(2);
+
+    for _ in 0..5 {
+        assert!(events(&mut monitor, VCC_OV).is_empty());
+        assert!(events(&mut monitor, 0).is_empty());
+    }
+    assert!(!monitor.is_overvoltage());
+}
+
+#[test]
+fn faults_are_debounced_independently() {
+    let mut monitor = SupplyMonitor::new(1);
+
+    assert_eq!(
+        events(&mut monitor, VCC_OV | VCC_UV),
+        vec![
+            event(SupplyFault::Overvoltage, true),
+            event(SupplyFault::Undervoltage, true),
+        ]
+    );
+    assert_eq!(
+        events(&mut monitor, VCC_OV),
+        vec![event(SupplyFault::Undervoltage, false)]
+    );
+    assert!(monitor.is_overvoltage());
+    assert!(!monitor.is_undervoltage());
+
+    let mut monitor = SupplyMonitor::new(0);
+    assert_eq!(events(&mut monitor, VCC_OV).len(), 1);
+}
+
+#[test]
+fn supply_faults_are_polled_from_the_device() {
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+    let mut monitor = SupplyMonitor::new(2);
+
+    tmag5170.enable_supply_alert().unwrap();
+    assert!(AlertConfig::form_u16(sim.register(Register::ALERT_CONFIG)).status_alrt_enable());
+
+    sim.set_register(Register::SYS_STATUS, VCC_OV);
+    assert_eq!(tmag5170.poll_supply(&mut monitor).unwrap().count(), 0);
+    let events: Vec<_> = tmag5170.poll_supply(&mut monitor).unwrap().collect();
+    assert_eq!(events, vec![event(SupplyFault::Overvoltage, true)]);
+}
+
+#[test]
+fn released_alert_pin_skips_the_read() {
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+    let mut monitor = SupplyMonitor::new(1);
+    sim.set_register(Register::SYS_STATUS, VCC_UV);
+
+    let frames = sim.frames();
+    let released = AlertPin { low: false };
+    let mut events = tmag5170
+        .poll_supply_on_alert(&released, &mut monitor)
+        .unwrap();
+    assert_eq!(events.next(), None);
+    assert_eq!(sim.frames(), frames);
+
+    let asserted = AlertPin { low: true };
+    let events: Vec<_> = tmag5170
+        .poll_supply_on_alert(&asserted, &mut monitor)
+        .unwrap()
+        .collect();
+    assert_eq!(events, vec![event(SupplyFault::Undervoltage, true)]);
+    assert_eq!(sim.frames(), frames + 1);
+}
+
+#[test]
+fn pulsed_alert_reaches_the_debounce_threshold() {
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+    let mut monitor = SupplyMonitor::new(3);
+    sim.set_register(Register::SYS_STATUS, VCC_OV);
+
+    let released = AlertPin { low: false };
+    let asserted = AlertPin { low: true };
+    for _ in 0..2 {
+        let mut events = tmag5170
+            .poll_supply_on_alert(&asserted, &mut monitor)
+            .unwrap();
+        assert_eq!(events.next(), None);
+        let mut events = tmag5170
+            .poll_supply_on_alert(&released, &mut monitor)
+            .unwrap();
+        assert_eq!(events.next(), None);
+    }
+
+    let events: Vec<_> = tmag5170
+        .poll_supply_on_alert(&asserted, &mut monitor)
+        .unwrap()
+        .collect();
+    assert_eq!(events, vec![event(SupplyFault::Overvoltage, true)]);
+}
+
+#[test]
+fn released_alert_pin_keeps_the_fault() {
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+    let mut monitor = SupplyMonitor::new(1);
+    sim.set_register(Register::SYS_STATUS, VCC_UV);
+
+    let asserted = AlertPin { low: true };
+    let released = AlertPin { low: false };
+    assert_eq!(
+        tmag5170
+            .poll_supply_on_alert(&asserted, &mut monitor)
+            .unwrap()
+            .count(),
+        1
+    );
+    for _ in 0..3 {
+        let mut events = tmag5170
+            .poll_supply_on_alert(&released, &mut monitor)
+            .unwrap();
+        assert_eq!(events.next(), None);
+    }
+    assert!(monitor.is_undervoltage());
+}
@@ -0,0 +1,95 @@
+use std::sync::Mutex;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use tmag5170::device_config::{DeviceConfig, OperatingMode};
+use tmag5170::retry::RetryPolicy;
+use tmag5170::sim::SimDevice;
+use tmag5170::system_config::{DataType, SystemConfig};
+use tmag5170::test_config::Version;
+use tmag5170::{Register, Tmag5170};
+
+static LINES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct Capture;
+
+impl Log for Capture {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Trace
+    }
+
+    fn log(&self, record: &Record) {
+        LINES.lock().unwrap().push(format!("{}", record.args()));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Capture = Capture;
+
+fn take_lines() -> Vec<String> {
+    std::mem::take(&mut *LINES.lock().unwrap())
+}
+
+#[test]
+fn traces_one_record_per_frame() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+
+    tmag5170
+        .apply_device_config(DeviceConfig::new().set_operating_mode(OperatingMode::Standby))
+        .unwrap();
+    let lines = take_lines();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(
+        lines[0],
+        "W DEVICE_CONFIG = 0x0010 cmd=0x0 crc=ok stat=0x400"
+    );
+
+    tmag5170.read_sys_status().unwrap();
+    let lines = take_lines();
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with("R SYS_STATUS = "), "{}", lines[0]);
+    assert!(
+        lines[0].ends_with("cmd=0x0 crc=ok stat=0x400"),
+        "{}",
+        lines[0]
+    );
+
+    tmag5170
+        .apply_system_config(SystemConfig::new().set_data_type(DataType::AM))
+        .unwrap();
+    tmag5170.read_am().unwrap();
+    let lines = take_lines();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("W SYSTEM_CONFIG = "), "{}", lines[0]);
+    assert!(lines[1].starts_with("R special = "), "{}", lines[1]);
+
+    // Regular reads are answered with special frames too
+    sim.set_register(Register::ANGLE_RESULT, 45 * 16);
+    sim.set_register(Register::MAGNITUDE_RESULT, 0x123);
+    sim.set_register(Register::SYS_STATUS, 0xffff);
+    tmag5170.read_sys_status().unwrap();
+    let stat = tmag5170.frame_status().to_u16();
+    let lines = take_lines();
+    assert_eq!(
+        lines,
+        vec![format!(
+            "R SYS_STATUS = 0x168 0x123 cmd=0x0 crc=ok stat={:#05x}",
+            stat
+        )]
+    );
+    assert_eq!(stat & 0x0ff, 0);
+    assert_ne!(stat, 0);
+    sim.set_register(Register::SYS_STATUS, 0x0000);
+
+    tmag5170.set_retry_policy(RetryPolicy::new().set_max_attempts(2));
+    sim.corrupt_responses(1);
+    tmag5170.read_sys_status().unwrap();
+    let lines = take_lines();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].ends_with("crc=BAD"), "{}", lines[0]);
+    assert!(lines[1].contains("crc=ok"), "{}", lines[1]);
+}
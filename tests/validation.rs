@@ -0,0 +1,171 @@
+use tmag5170::alert_config::{AlertConfig, AlertMode};
+use tmag5170::device_config::{DeviceConfig, OperatingMode};
+use tmag5170::sensor_config::{AngleEn, MagChEn, SensorConfig};
+use tmag5170::sim::SimDevice;
+use tmag5170::system_config::{DataType, SystemConfig};
+use tmag5170::test_config::Version;
+use tmag5170::validation::{Axis, ConfigIssue, ConfigRegister, ConfigSet, Field, ValidationMode};
+use tmag5170::{ExtError, Register, Tmag5170};
+
+fn xyz() -> ConfigSet {
+    ConfigSet {
+        device: DeviceConfig::new().set_t_en(true),
+        sensor: SensorConfig::new().set_mag_ch_en(MagChEn::Xyz),
+        ..ConfigSet::default()
+    }
+}
+
+fn issues(config: ConfigSet) -> Vec<ConfigIssue> {
+    let issues = config.validate();
+    assert_eq!(issues.len(), issues.iter().count());
+    assert_eq!(issues.is_empty(), issues.first().is_none());
+    issues.iter().collect()
+}
+
+#[test]
+fn consistent_configs_have_no_issues() {
+    assert!(issues(ConfigSet::default()).is_empty());
+
+    let mut config = xyz();
+    config.sensor = config.sensor.set_angle_en(AngleEn::Yz);
+    config.system = SystemConfig::new()
+        .set_data_type(DataType::AM)
+        .set_t_x_limit_check(true);
+    config.alert = AlertConfig::new().set_z_thrx_alrt_enable(true);
+    assert!(issues(config).is_empty());
+}
+
+#[test]
+fn reserved_codes_and_bits_are_reported() {
+    let config = ConfigSet {
+        device: DeviceConfig::form_u16(0x0070 | 0x0080),
+        alert: AlertConfig::form_u16(0x4000),
+        ..ConfigSet::default()
+    };
+    assert_eq!(
+        issues(config),
+        vec![
+            ConfigIssue::ReservedValue(Field::OperatingMode),
+            ConfigIssue::ReservedBits(ConfigRegister::Device, 0x0080),
+            ConfigIssue::ReservedBits(ConfigRegister::Alert, 0x4000),
+        ]
+    );
+}
+
+#[test]
+fn disabled_channels_are_reported() {
+    let config = ConfigSet {
+        device: DeviceConfig::new().set_t_limit_check_en(true),
+        sensor: SensorConfig::new()
+            .set_mag_ch_en(MagChEn::X)
+            .set_angle_en(AngleEn::Xy),
+        system: SystemConfig::new()
+            .set_data_type(DataType::ZT)
+            .set_t_y_limit_check(true),
+        alert: AlertConfig::new()
+            .set_z_thrx_alrt_enable(true)
+            .set_t_thrx_alrt_enable(true),
+    };
+    assert_eq!(
+        issues(config),
+        vec![
+            ConfigIssue::AngleAxisDisabled(AngleEn::Xy, Axis::Y),
+            ConfigIssue::DataTypeAxisDisabled(DataType::ZT, Axis::Z),
+            ConfigIssue::DataTypeTempDisabled(DataType::ZT),
+            ConfigIssue::LimitCheckAxisDisabled(Axis::Y),
+            ConfigIssue::TempLimitCheckDisabled,
+            ConfigIssue::ThresholdAlertAxisDisabled(Axis::Z),
+            ConfigIssue::TempThresholdAlertDisabled,
+        ]
+    );
+}
+
+#[test]
+fn limit_checks_without_channel_are_reported() {
+    use Axis::{X, Y, Z};
+
+    // (channels, limit checks on X Y Z, axes reported)
+    let cases: [(MagChEn, [bool; 3], &[Axis]); 9] = [
+        (MagChEn::Off, [false; 3], &[]),
+        (MagChEn::Off, [true; 3], &[X, Y, Z]),
+        (MagChEn::X, [true, false, false], &[]),
+        (MagChEn::X, [false, true, true], &[Y, Z]),
+        (MagChEn::Yz, [true, true, false], &[X]),
+        (MagChEn::Zx, [false, true, false], &[Y]),
+        (MagChEn::Xy, [false, false, true], &[Z]),
+        (MagChEn::Zyz, [true, true, true], &[X]),
+        (MagChEn::Xyz, [true; 3], &[]),
+    ];
+    for &(mag_ch_en, [x, y, z], axes) in cases.iter() {
+        let config = ConfigSet {
+            sensor: SensorConfig::new().set_mag_ch_en(mag_ch_en),
+            system: SystemConfig::new()
+                .set_t_x_limit_check(x)
+                .set_t_y_limit_check(y)
+                .set_t_z_limit_check(z),
+            ..ConfigSet::default()
+        };
+        let expected: Vec<ConfigIssue> = axes
+            .iter()
+            .map(|&axis| ConfigIssue::LimitCheckAxisDisabled(axis))
+            .collect();
+        assert_eq!(issues(config), expected, "{:?} {:?}", mag_ch_en, [x, y, z]);
+    }
+
+    for &t_en in [false, true].iter() {
+        let config = ConfigSet {
+            device: DeviceConfig::new()
+                .set_t_en(t_en)
+                .set_t_limit_check_en(true),
+            ..ConfigSet::default()
+        };
+        let expected = if t_en {
+            vec![]
+        } else {
+            vec![ConfigIssue::TempLimitCheckDisabled]
+        };
+        assert_eq!(issues(config), expected);
+    }
+}
+
+#[test]
+fn contradictory_settings_are_reported() {
+    let mut config = xyz();
+    config.system = SystemConfig::new().set_data_type(DataType::AM);
+    config.alert = AlertConfig::new()
+        .set_alert_mode(AlertMode::Comparator)
+        .set_rslt_alrt_enable(true);
+
+    assert_eq!(
+        issues(config),
+        vec![
+            ConfigIssue::AmWithoutAngle,
+            ConfigIssue::ResultAlertInComparatorMode,
+        ]
+    );
+}
+
+#[test]
+fn rejected_config_is_not_written() {
+    let sim = SimDevice::new(Version::A1);
+    let mut tmag5170 = Tmag5170::new(sim.spi(), sim.cs());
+
+    let mut config = xyz();
+    config.device = config.device.set_operating_mode(OperatingMode::Standby);
+    config.system = SystemConfig::new().set_data_type(DataType::AM);
+    assert_eq!(
+        tmag5170.apply_config(config, ValidationMode::Reject),
+        Err(ExtError::InvalidConfig(ConfigIssue::AmWithoutAngle))
+    );
+    assert_eq!(sim.register(Register::DEVICE_CONFIG), 0x0000);
+    assert_eq!(sim.register(Register::SENSOR_CONFIG), 0x0000);
+    assert_eq!(tmag5170.config(), ConfigSet::default());
+
+    let issues = tmag5170.apply_config(config, ValidationMode::Warn).unwrap();
+    assert_eq!(issues.first(), Some(ConfigIssue::AmWithoutAngle));
+    assert_eq!(
+        sim.register(Register::DEVICE_CONFIG),
+        config.device.to_u16()
+    );
+    assert_eq!(tmag5170.config(), config);
+}